@@ -4,7 +4,10 @@ extern crate hungarian;
 extern crate pathfinding;
 
 use criterion::Criterion;
-use hungarian::minimize;
+use hungarian::{minimize, minimize_binary, minimize_feasible, minimize_k_best, minimize_shuffled, minimize_u32};
+use hungarian::{product_matrix, sequential_matrix, staircase_matrix};
+#[cfg(feature = "flow")]
+use hungarian::minimize_flow;
 use pathfinding::kuhn_munkres::kuhn_munkres_min;
 use pathfinding::matrix::Matrix;
 
@@ -12,14 +15,7 @@ fn bench_hungarian(c: &mut Criterion) {
     c.bench_function_over_inputs(
         "hungarian_NxN",
         |b, &&max| {
-            let mut matrix = vec![0; max * max];
-            let mut n = 0;
-            for i in 0..max {
-                for j in 0..max {
-                    matrix[max * i + j] = n;
-                    n += 1;
-                }
-            }
+            let matrix = sequential_matrix(max, max).into_iter().map(|c| c as i32).collect::<Vec<_>>();
             b.iter(move || minimize(&matrix, max, max))
         },
         &[5, 10, 25, 50, 100],
@@ -30,28 +26,176 @@ fn bench_hungarian_worst_case(c: &mut Criterion) {
     c.bench_function_over_inputs(
         "hungarian_worst_case_NxN",
         |b, &&max| {
-            let mut matrix = vec![0; max * max];
+            let matrix = product_matrix(max).into_iter().map(|c| c as i32).collect::<Vec<_>>();
+            b.iter(move || minimize(&matrix, max, max))
+        },
+        &[5, 10, 25, 50],
+    );
+}
+
+fn bench_hungarian_shuffled_worst_case(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "hungarian_shuffled_worst_case_NxN",
+        |b, &&max| {
+            let matrix = product_matrix(max).into_iter().map(|c| c as i32).collect::<Vec<_>>();
+            b.iter(move || minimize_shuffled(&matrix, max, max, 0))
+        },
+        &[5, 10, 25, 50],
+    );
+}
+
+fn bench_hungarian_staircase(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "hungarian_staircase_NxN",
+        |b, &&max| {
+            let matrix = staircase_matrix(max).into_iter().map(|c| c as i32).collect::<Vec<_>>();
+            b.iter(move || minimize(&matrix, max, max))
+        },
+        &[25, 50, 100, 200],
+    );
+}
+
+fn bench_hungarian_shuffled_staircase(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "hungarian_shuffled_staircase_NxN",
+        |b, &&max| {
+            let matrix = staircase_matrix(max).into_iter().map(|c| c as i32).collect::<Vec<_>>();
+            b.iter(move || minimize_shuffled(&matrix, max, max, 0))
+        },
+        &[25, 50, 100, 200],
+    );
+}
+
+fn bench_hungarian_greedy(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "hungarian_greedy_NxN",
+        |b, &&max| {
+            let mut matrix = vec![1; max * max];
             for i in 0..max {
-                for j in 0..max {
-                    matrix[max * i + j] = ((i + 1) * (j + 1)) as i32;
-                }
+                matrix[max * i + max - 1 - i] = 0;
             }
             b.iter(move || minimize(&matrix, max, max))
         },
-        &[5, 10, 25, 50],
+        &[5, 10, 25, 50, 100],
     );
 }
 
+fn bench_hungarian_u32(c: &mut Criterion) {
+    let max = 500;
+    let matrix = sequential_matrix(max, max).into_iter().map(|c| c as u32).collect::<Vec<_>>();
+    c.bench_function("hungarian_u32_NxN/generic/500", |b| b.iter(|| minimize(&matrix, max, max)));
+    c.bench_function("hungarian_u32_NxN/wrapper/500", |b| b.iter(|| minimize_u32(&matrix, max, max)));
+}
+
+fn bench_hungarian_degenerate(c: &mut Criterion) {
+    // A single row or column takes linear time, without the full workspace
+    let max = 100_000;
+    let matrix = (0..max as u32).map(|k| k.wrapping_mul(2_654_435_761) % 1000).collect::<Vec<_>>();
+    c.bench_function("hungarian_degenerate/1xN/100000", |b| b.iter(|| minimize(&matrix, 1, max)));
+    c.bench_function("hungarian_degenerate/Nx1/100000", |b| b.iter(|| minimize(&matrix, max, 1)));
+}
+
+/// Inputs for the sparse benchmarks: matrix size and percentage of available entries.
+const SPARSE: &[(usize, u64)] = &[(25, 100), (25, 10), (100, 100), (100, 10)];
+
+/// Random `max * max` matrix where roughly `percent`% of entries are available,
+/// and the rest are set to `u32::MAX`.
+fn sparse_matrix(max: usize, percent: u64) -> Vec<u32> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..max * max)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state % 100 < percent { (state >> 32) as u32 % 1000 } else { u32::MAX }
+        })
+        .collect()
+}
+
+fn bench_hungarian_random(c: &mut Criterion) {
+    // Large enough that the matrix falls out of cache, so the memory
+    // access pattern of the Step 6 dual update shows up in the total
+    let max = 1000;
+    let matrix = sparse_matrix(max, 100);
+    c.bench_function("hungarian_random_NxN/1000", |b| b.iter(|| minimize(&matrix, max, max)));
+
+    // Twice as many rows as columns, which is solved on the transposed matrix
+    let max = 500;
+    let mut tall = sparse_matrix(2 * max, 100);
+    tall.truncate(2 * max * max);
+    c.bench_function("hungarian_random_2NxN/500", |b| b.iter(|| minimize(&tall, 2 * max, max)));
+}
+
+fn bench_reduction(c: &mut Criterion) {
+    // Each row's unique minimum is on the diagonal, so Step 1 leaves a complete
+    // matching of zeros, and the row reduction is most of the work
+    for &max in &[500, 1000] {
+        let noise = sparse_matrix(max, 100);
+        let matrix = (0..max * max)
+            .map(|k| ((k % max + max - k / max) % max) as u32 * 1000 + noise[k] % 1000)
+            .collect::<Vec<_>>();
+        c.bench_function(&format!("hungarian_reduction_NxN/{}", max), |b| b.iter(|| minimize(&matrix, max, max)));
+
+        // The same with twice as many rows, where each column's unique minimum
+        // is on the diagonal, and Step 1 reduces the columns instead
+        let noise = sparse_matrix(2 * max, 100);
+        let tall = (0..2 * max * max)
+            .map(|k| ((k / max + 2 * max - k % max) % (2 * max)) as u32 * 1000 + noise[k] % 1000)
+            .collect::<Vec<_>>();
+        c.bench_function(&format!("hungarian_reduction_2NxN/{}", max), |b| b.iter(|| minimize(&tall, 2 * max, max)));
+    }
+}
+
+fn bench_hungarian_sparse(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "hungarian_sparse_NxN_percent",
+        |b, &&(max, percent)| {
+            let matrix = sparse_matrix(max, percent);
+            b.iter(move || minimize_feasible(&matrix, max, max))
+        },
+        SPARSE,
+    );
+}
+
+#[cfg(feature = "flow")]
+fn bench_flow_sparse(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "flow_sparse_NxN_percent",
+        |b, &&(max, percent)| {
+            let matrix = sparse_matrix(max, percent);
+            b.iter(move || minimize_flow(&matrix, max, max))
+        },
+        SPARSE,
+    );
+}
+
+fn bench_binary(c: &mut Criterion) {
+    for &max in &[25, 100] {
+        let costs = sparse_matrix(max, 50)
+            .into_iter()
+            .map(|c| if c == u32::MAX { 1 } else { 0 })
+            .collect::<Vec<u32>>();
+        let matrix = costs.iter().map(|&c| c == 1).collect::<Vec<_>>();
+        c.bench_function(&format!("binary_NxN/hungarian/{}", max), |b| b.iter(|| minimize(&costs, max, max)));
+        c.bench_function(&format!("binary_NxN/matching/{}", max), |b| b.iter(|| minimize_binary(&matrix, max, max)));
+    }
+}
+
+fn bench_k_best(c: &mut Criterion) {
+    let (max, k) = (100, 50);
+    let matrix = sparse_matrix(max, 100);
+    c.bench_function("k_best_NxN/100/50", |b| b.iter(|| minimize_k_best(&matrix, max, max, k)));
+}
+
 fn bench_pathfinding_hungarian(c: &mut Criterion) {
     c.bench_function_over_inputs(
         "pathfinding_hungarian_NxN",
         |b, &&max| {
+            let costs = sequential_matrix(max, max);
             let mut matrix = Matrix::new(max, max, 0);
-            let mut n: i32 = 0;
             for i in 0..max {
                 for j in 0..max {
-                    matrix[&(i, j)] = n;
-                    n += 1;
+                    matrix[&(i, j)] = costs[max * i + j] as i32;
                 }
             }
             b.iter(move || kuhn_munkres_min(&matrix))
@@ -64,10 +208,11 @@ fn bench_pathfinding_hungarian_worst_case(c: &mut Criterion) {
     c.bench_function_over_inputs(
         "pathfinding_hungarian_worst_case_NxN",
         |b, &&max| {
+            let costs = product_matrix(max);
             let mut matrix = Matrix::new(max, max, 0);
             for i in 0..max {
                 for j in 0..max {
-                    matrix[&(i, j)] = ((i + 1) * (j + 1)) as i32;
+                    matrix[&(i, j)] = costs[max * i + j] as i32;
                 }
             }
             b.iter(move || kuhn_munkres_min(&matrix))
@@ -80,8 +225,34 @@ criterion_group!(
     benches,
     bench_hungarian,
     bench_hungarian_worst_case,
+    bench_hungarian_shuffled_worst_case,
+    bench_hungarian_staircase,
+    bench_hungarian_shuffled_staircase,
+    bench_hungarian_greedy,
+    bench_hungarian_degenerate,
     bench_pathfinding_hungarian,
     bench_pathfinding_hungarian_worst_case,
 );
 
-criterion_main!(benches);
+criterion_group!(
+    name = large;
+    config = Criterion::default().sample_size(10);
+    targets = bench_hungarian_u32, bench_hungarian_random, bench_reduction,
+);
+
+criterion_group!(sparse, bench_hungarian_sparse, bench_binary);
+
+criterion_group!(
+    name = k_best;
+    config = Criterion::default().sample_size(10);
+    targets = bench_k_best,
+);
+
+#[cfg(feature = "flow")]
+criterion_group!(flow, bench_flow_sparse);
+
+#[cfg(not(feature = "flow"))]
+criterion_main!(benches, large, sparse, k_best);
+
+#[cfg(feature = "flow")]
+criterion_main!(benches, large, sparse, k_best, flow);
@@ -0,0 +1,90 @@
+//! Solves an assignment problem read from a CSV file, or from standard input.
+//!
+//! Each line is a row of integer costs, separated by commas. Blank lines and
+//! lines starting with `#` are skipped. The matrix may be rectangular.
+//!
+//! ```text
+//! $ printf '5,5\n1,10\n2,3\n' | cargo run --example assign
+//!   5    5   (unassigned)
+//! [ 1]  10
+//!   2  [ 3]
+//! cost: 4
+//! unassigned rows: 0
+//! unassigned columns: none
+//! ```
+
+extern crate hungarian;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
+use hungarian::{format_assignment, minimize_nested};
+
+/// Parses CSV rows of costs, along with the line each row came from,
+/// reporting the line and field of the first bad entry.
+fn parse(input: &str) -> Result<(Vec<Vec<i64>>, Vec<usize>), String> {
+    let mut rows = Vec::new();
+    let mut lines = Vec::new();
+    for (line, text) in input.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') { continue }
+
+        let row = text.split(',')
+            .enumerate()
+            .map(|(field, cell)| {
+                cell.trim().parse::<i64>().map_err(|error| {
+                    format!("line {}, field {}: invalid cost {:?} ({})", line + 1, field + 1, cell.trim(), error)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.push(row);
+        lines.push(line + 1);
+    }
+    Ok((rows, lines))
+}
+
+fn run() -> Result<(), String> {
+    let mut input = String::new();
+    match env::args().nth(1) {
+        Some(path) => File::open(&path)
+            .and_then(|mut file| file.read_to_string(&mut input))
+            .map_err(|error| format!("{}: {}", path, error))?,
+        None => io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|error| format!("stdin: {}", error))?,
+    };
+
+    let (rows, lines) = parse(&input)?;
+    let assignment = minimize_nested(&rows).map_err(|error| {
+        format!("line {}: expected {} costs, found {}", lines[error.row], error.expected, error.found)
+    })?;
+
+    let height = rows.len();
+    let width = rows.first().map_or(0, Vec::len);
+    let matrix = rows.concat();
+    print!("{}", format_assignment(&matrix, height, width, &assignment));
+
+    let list = |unassigned: Vec<usize>| {
+        if unassigned.is_empty() {
+            String::from("none")
+        } else {
+            unassigned.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")
+        }
+    };
+
+    let rows = (0..height).filter(|&i| assignment[i].is_none()).collect();
+    let columns = (0..width).filter(|&j| !assignment.contains(&Some(j))).collect();
+    println!("unassigned rows: {}", list(rows));
+    println!("unassigned columns: {}", list(columns));
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,41 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate hungarian;
+
+use hungarian::minimize;
+
+/// Largest dimension to fuzz, to keep each iteration fast.
+const MAX_DIM: usize = 32;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 { return }
+
+    let height = data[0] as usize % (MAX_DIM + 1);
+    let width = data[1] as usize % (MAX_DIM + 1);
+
+    // Enforce `matrix.len() == height * width` by cycling through the input
+    let bytes = &data[2..];
+    if bytes.is_empty() && height * width > 0 { return }
+    let matrix = (0..height * width)
+        .map(|k| i32::from(bytes[k % bytes.len()] as i8))
+        .collect::<Vec<_>>();
+
+    let assignment = minimize(&matrix, height, width);
+    check(&assignment, height, width);
+});
+
+/// Checks that `assignment` is a valid maximal matching for a `height * width` matrix.
+fn check(assignment: &[Option<usize>], height: usize, width: usize) {
+    assert_eq!(assignment.len(), height);
+
+    let mut used = vec![false; width];
+    for &j in assignment.iter().filter_map(Option::as_ref) {
+        assert!(j < width, "column {} out of bounds", j);
+        assert!(!used[j], "column {} assigned twice", j);
+        used[j] = true;
+    }
+
+    let matched = assignment.iter().filter(|j| j.is_some()).count();
+    assert_eq!(matched, height.min(width));
+}
@@ -2,20 +2,54 @@ extern crate fixedbitset;
 extern crate num_traits;
 extern crate ndarray;
 
+#[cfg(feature = "csv")]
+extern crate csv;
+
+#[cfg(feature = "petgraph")]
+extern crate petgraph;
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(test)]
+extern crate ordered_float;
+
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+
 use fixedbitset::FixedBitSet;
-use num_traits::{PrimInt, NumAssign};
+use num_traits::{PrimInt, NumAssign, Signed};
 use ndarray::prelude::Array2;
+use ndarray::{ArrayBase, ArrayViewMut2, DataMut, Ix2};
 
 /// Internal macro for indexing an Array2 without the bounds check
+#[cfg(not(feature = "safe-indexing"))]
 macro_rules! get {
     ($m:expr, $i:expr, $j:expr) => (unsafe { *$m.uget(($i, $j)) })
 }
 
 /// Internal macro for mutating an Array2 without the bounds check
+#[cfg(not(feature = "safe-indexing"))]
 macro_rules! set {
     ($m:expr, $i:expr, $j:expr, $v: expr) => (unsafe { *$m.uget_mut(($i, $j)) = $v; })
 }
 
+/// Internal macro for indexing an Array2, with the bounds check
+/// enabled by the `safe-indexing` feature.
+#[cfg(feature = "safe-indexing")]
+macro_rules! get {
+    ($m:expr, $i:expr, $j:expr) => ($m[($i, $j)])
+}
+
+/// Internal macro for mutating an Array2, with the bounds check
+/// enabled by the `safe-indexing` feature.
+#[cfg(feature = "safe-indexing")]
+macro_rules! set {
+    ($m:expr, $i:expr, $j:expr, $v: expr) => ({ $m[($i, $j)] = $v; })
+}
+
 /// Internal macro for querying a FixedBitSet.
 /// Syntactic sugar for `s[i]`, but without the runtime overhead of the Index trait.
 macro_rules! on {
@@ -55,6 +89,37 @@ macro_rules! off {
 ///     - `Some(j)` if row `i` should be assigned to column `j`
 ///     - `None` if row `i` is not in the optimal assignment. Only possible if `width < height`.
 ///
+/// `v` always has exactly `height` entries, one per row, whichever of `height` and
+/// `width` is larger. In particular, a matrix with zero rows has an empty assignment,
+/// and a matrix with zero columns leaves every row as `None`.
+///
+/// A single row or column is solved directly, by picking its cheapest entry: this
+/// takes `O(width)` or `O(height)` time, and allocates nothing besides the output.
+/// Every other shape is solved in a `height * width` workspace.
+///
+/// # Cost types
+///
+/// Costs can be of any primitive integer type, which is also the type every
+/// intermediate reduced cost is computed in, with the usual primitive arithmetic:
+/// an overflow panics in debug builds, and wraps in release builds.
+/// [`max_safe_cost`](fn.max_safe_cost.html) gives a bound under which it can't
+/// happen. To compute in a wider type instead, see [`minimize_as`](fn.minimize_as.html).
+///
+/// Types with wrapping arithmetic, such as `std::num::Wrapping<i32>`, don't implement
+/// `PrimInt`, so they're rejected at compile time instead of silently producing a
+/// wrong answer:
+///
+/// ```rust,compile_fail
+/// extern crate hungarian;
+///
+/// use std::num::Wrapping;
+///
+/// fn main() {
+///     let matrix = vec![Wrapping(1i32), Wrapping(2), Wrapping(3), Wrapping(4)];
+///     let _ = hungarian::minimize(&matrix, 2, 2);
+/// }
+/// ```
+///
 /// # Panics
 ///
 /// This function uses unsafe array indexing directly in order to minimize,
@@ -64,6 +129,10 @@ macro_rules! off {
 /// If given correct arguments, there is no way we can index out of bounds,
 /// even in unsafe blocks: we only ever iterate between 0 and width/height.
 ///
+/// Also panics if `height * width` overflows `usize`, which is possible on 32-bit
+/// targets, instead of indexing with a wrapped-around offset.
+/// [`try_minimize`](fn.try_minimize.html) returns an error instead.
+///
 /// # Examples
 ///
 /// ```rust
@@ -146,10 +215,39 @@ macro_rules! off {
 ///
 /// [1]: http://csclab.murraystate.edu/~bob.pilgrim/445/munkres.html
 ///
+#[must_use]
 pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>> {
+    assert!(height.checked_mul(width).is_some(), "minimize: {} x {} matrix overflows usize", height, width);
+    solve_assignment(&(matrix, height, width))
+}
+
+/// Internal implementation of `minimize`, where `cost(i, j)` computes
+/// the non-negative cost of entry `(i, j)`.
+fn minimize_by<N, F>(height: usize, width: usize, cost: F) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt,
+          F: Fn(usize, usize) -> N,
+{
+
+    // The fast paths below never reach `solve`, so don't leave a stale count
+    #[cfg(test)]
+    ITERATIONS.with(|n| n.set(0));
 
     // No possible assignment
-    if height == 0 || width == 0 { return Vec::new() }
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    // Single row: assign it to the cheapest column
+    if height == 1 {
+        return vec![argmin((0..width).map(|j| cost(0, j)))]
+    }
+
+    // Single column: assign it to the cheapest row
+    if width == 1 {
+        let mut result = vec![None; height];
+        if let Some(i) = argmin((0..height).map(|i| cost(i, 0))) {
+            result[i] = Some(0);
+        }
+        return result
+    }
 
     //********************************************//
     //                                            //
@@ -157,25 +255,230 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
     //                                            //
     //********************************************//
 
-    // Rotate matrix if width < height
-    let rotated = width < height;
-    let (w, h) = if rotated { (height, width) } else { (width, height) };
-    let mut m = Array2::zeros((h, w));
+    // View the matrix transposed if width < height
+    let mut m = orient(height, width, cost);
 
-    // Clamp matrix to be positive and rotate if necessary
-    for i in 0..height {
-        for j in 0..width {
-            let cost = matrix[width * i + j];
-            if cost < N::zero() {
-                continue
-            } else if rotated {
-                set!(m, width - 1 - j, i, cost)
-            } else {
-                set!(m, i, j, cost)
+    // Without forbidden entries, every row can always be matched
+    let stars = solve(&mut m, None).unwrap();
+    assignment(&stars, width < height)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that starts from a prior
+/// assignment `hint`, such as the result of solving a nearby matrix.
+///
+/// Each hinted entry that is a zero after the row reduction is starred before
+/// the main loop, so a good hint leaves fewer rows to match. The result is
+/// optimal either way, though ties may be broken differently than `minimize`.
+///
+/// The hint is ignored unless it is a partial matching: one entry per row,
+/// every column in bounds, and no column used twice.
+///
+/// ```
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_hinted};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 3, 6,
+///         7, 8, 9,
+///     ];
+///
+///     let previous = minimize(&matrix, 3, 3);
+///
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 3, 6,
+///         7, 8, 8,
+///     ];
+///
+///     assert_eq!(minimize_hinted(&matrix, 3, 3, &previous), minimize(&matrix, 3, 3));
+/// }
+/// ```
+#[must_use]
+pub fn minimize_hinted<N>(matrix: &[N], height: usize, width: usize, hint: &[Option<usize>]) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    // The single row and column cases have nothing to seed
+    if height <= 1 || width <= 1 { return minimize(matrix, height, width) }
+
+    let transposed = width < height;
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    let (h, w) = m.dim();
+
+    let mut stars = Array2::from_elem((h, w), false);
+    let mut used = FixedBitSet::with_capacity(width);
+    let valid = hint.len() == height && hint.iter().all(|&j| match j {
+        Some(j) if j < width && off!(used, j) => { used.insert(j); true }
+        Some(_) => false,
+        None => true,
+    });
+
+    if valid {
+        for (i, &j) in hint.iter().enumerate() {
+            match j {
+                Some(j) if transposed => set!(stars, j, i, true),
+                Some(j) => set!(stars, i, j, true),
+                None => (),
             }
         }
     }
 
+    let mut primes = Array2::from_elem((h, w), false);
+    let mut row_cover = FixedBitSet::with_capacity(h);
+    let mut col_cover = FixedBitSet::with_capacity(w);
+    let mut columns = Vec::with_capacity(w);
+
+    // Without forbidden entries, every row can always be matched
+    solve_in(&mut m, None, &mut stars, &mut primes, &mut row_cover, &mut col_cover, &mut columns, &mut unobserved);
+    assignment(&stars, transposed)
+}
+
+/// Returns the index of the first minimum element, matching the
+/// tie-breaking of the full algorithm on a single row or column.
+fn argmin<N: Ord + Copy, I: Iterator<Item = N>>(costs: I) -> Option<usize> {
+    costs.enumerate()
+        .min_by_key(|&(_, cost)| cost)
+        .map(|(k, _)| k)
+}
+
+/// Clamps a cost to be greater or equal to zero.
+///
+/// Every entry goes through here before reaching the algorithm, so negative
+/// entries down to `N::min_value()` never take part in its arithmetic.
+fn clamp<N: NumAssign + PrimInt>(cost: &N) -> N {
+    if *cost < N::zero() { N::zero() } else { *cost }
+}
+
+/// Fills a workspace of shape `(h, w)` with `h <= w` from a `height * width`
+/// matrix, where `f(i, j)` computes entry `(i, j)`.
+///
+/// If `width < height`, the workspace holds the transposed matrix, so that original
+/// entry `(i, j)` is found at `(j, i)`. The algorithm then matches each column of
+/// the original matrix to a row, instead of the other way around. Either way the
+/// workspace is laid out in row-major order, so the row-at-a-time passes of Steps 1
+/// and 6 stay contiguous in memory.
+fn orient<U, F>(height: usize, width: usize, f: F) -> Array2<U>
+    where F: Fn(usize, usize) -> U,
+{
+    if width < height {
+        Array2::from_shape_fn((width, height), |(j, i)| f(i, j))
+    } else {
+        Array2::from_shape_fn((height, width), |(i, j)| f(i, j))
+    }
+}
+
+/// Internal invariant check: panics unless `stars` marks a matching of every row
+/// in a workspace of shape `(h, w)` with `h <= w`, i.e. each row has exactly one
+/// star, and no column has more than one.
+///
+/// Every assignment returned is read off such a matching, so this catches a
+/// broken star or prime update before it turns into a repeated column.
+fn check_stars(stars: &Array2<bool>) {
+    let (_, w) = stars.dim();
+    let mut used = FixedBitSet::with_capacity(w);
+    for (i, row) in stars.genrows().into_iter().enumerate() {
+        let mut starred = row.iter().enumerate().filter(|&(_, &v)| v).map(|(j, _)| j);
+        let j = starred.next().unwrap_or_else(|| panic!("invariant violated: row {} has no star", i));
+        assert!(starred.next().is_none(), "invariant violated: row {} has several stars", i);
+        assert!(off!(used, j), "invariant violated: column {} is starred twice", j);
+        used.insert(j);
+    }
+}
+
+/// Converts a set of starred zeros in a workspace produced by `orient`
+/// back into an assignment in the original orientation.
+///
+/// With the `invariants` feature, checks the stars with `check_stars` first.
+fn assignment(stars: &Array2<bool>, transposed: bool) -> Vec<Option<usize>> {
+
+    if cfg!(feature = "invariants") { check_stars(stars) }
+
+    let (h, w) = stars.dim();
+
+    let assign = stars.genrows().into_iter().map(|r| {
+        r.iter().enumerate()
+            .find(|&(_, &v)| v)
+            .map(|(i, _)| i)
+    });
+
+    // Each starred row of a transposed workspace is an original column
+    if transposed {
+        let mut result = vec![None; w];
+        assign.enumerate().for_each(|(j, i)| {
+            if let Some(i) = i { result[i] = Some(j) }
+        });
+        result
+    } else {
+        let mut result = Vec::with_capacity(h);
+        result.extend(assign);
+        result
+    }
+}
+
+/// Inverse of `orient`: copies a workspace back into a `height * width`
+/// matrix in the original orientation, laid out in row-major order.
+fn unorient<U: Clone>(m: &Array2<U>, height: usize, width: usize) -> Array2<U> {
+    if width < height {
+        m.t().as_standard_layout().into_owned()
+    } else {
+        m.clone()
+    }
+}
+
+/// Internal macro for checking whether an entry may be matched.
+/// Every entry is allowed if there is no mask.
+macro_rules! allowed {
+    ($a:expr, $i:expr, $j:expr) => ($a.map_or(true, |a| get!(a, $i, $j)))
+}
+
+/// Number of independent running minimums kept by `lane_min`.
+#[cfg(feature = "simd")]
+const LANES: usize = 8;
+
+/// Finds the smallest entry of the non-empty slice `row`.
+///
+/// Keeps a separate running minimum in each of `LANES` lanes, so the comparisons
+/// don't form a single dependency chain and compile down to vector instructions
+/// for the primitive integer types. The result is the same as `row.iter().min()`.
+#[cfg(feature = "simd")]
+fn lane_min<N: Copy + Ord>(row: &[N]) -> N {
+    let mut chunks = row.chunks_exact(LANES);
+    let mut lanes = [row[0]; LANES];
+    for chunk in &mut chunks {
+        for (lane, &v) in lanes.iter_mut().zip(chunk) {
+            *lane = (*lane).min(v);
+        }
+    }
+    lanes.iter()
+        .chain(chunks.remainder())
+        .cloned()
+        .min()
+        .unwrap()
+}
+
+// Number of passes through the main loop of the most recent `solve` on this
+// thread, so tests can bound the work done independently of wall-clock time.
+#[cfg(test)]
+thread_local!(static ITERATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+/// Core of the Hungarian algorithm: Steps 1 through 6.
+///
+/// Takes a non-negative workspace `m` of shape `(h, w)` with `h <= w`, and
+/// an optional mask of the same shape where `false` marks a forbidden entry.
+///
+/// Returns the set of starred zeros, which marks a minimum cost matching
+/// of every row, or `None` if the forbidden entries make it impossible
+/// to match every row. On success, `m` is left holding the reduced costs
+/// at the optimum, which are zero on every starred entry.
+///
+/// Entries are copied in and out of the workspace by `get!` and `set!` in every
+/// step, so the cost type must be `Copy` rather than merely `Clone`.
+fn solve<N: NumAssign + Copy + Ord>(m: &mut Array2<N>, allowed: Option<&Array2<bool>>) -> Option<Array2<bool>> {
+
+    let (h, w) = m.dim();
+
     // The set of starred zero entries
     let mut stars = Array2::from_elem((h, w), false);
 
@@ -188,44 +491,176 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
     // The set of covered column indices
     let mut col_cover = FixedBitSet::with_capacity(w);
 
+    // The column indices visited by [Step 6]
+    let mut columns = Vec::with_capacity(w);
+
+    if solve_in(m, allowed, &mut stars, &mut primes, &mut row_cover, &mut col_cover, &mut columns, &mut unobserved) {
+        Some(stars)
+    } else {
+        None
+    }
+}
+
+/// Observer for `solve_in` that ignores every step.
+fn unobserved<M>(_: u8, _: &M, _: &Array2<bool>, _: &Array2<bool>, _: &FixedBitSet, _: &FixedBitSet) {}
+
+/// Variant of `solve` that runs in caller-provided buffers, so they can be
+/// reused across calls. `stars` and `primes` must have the same shape as `m`,
+/// and the bit sets must have capacity for its rows and columns respectively.
+/// `columns` holds the column indices that Step 6 visits, and only allocates if
+/// it has capacity for fewer than `w` of them. `primes`, the bit sets and `columns`
+/// are reset before use. `m` can be an owned array, or a mutable view of the
+/// caller's costs, as in `minimize_in_place`.
+///
+/// Any entries already starred in `stars` seed Step 2: each one that is an
+/// allowed zero after Step 1, and shares its row and column with no earlier
+/// seed, is kept as a starred zero, and the rest are cleared. Seeding only
+/// changes where the search starts, never the optimality of the result.
+///
+/// `observe` is called with the step number and the current state whenever a step
+/// of the algorithm is entered.
+///
+/// Returns whether every row was matched, in which case `stars` holds the result.
+#[allow(clippy::too_many_arguments)]
+fn solve_in<N, S, O>(
+    m: &mut ArrayBase<S, Ix2>,
+    allowed: Option<&Array2<bool>>,
+    stars: &mut Array2<bool>,
+    primes: &mut Array2<bool>,
+    row_cover: &mut FixedBitSet,
+    col_cover: &mut FixedBitSet,
+    columns: &mut Vec<usize>,
+    observe: &mut O,
+) -> bool
+    where N: NumAssign + Copy + Ord,
+          S: DataMut<Elem = N>,
+          O: FnMut(u8, &ArrayBase<S, Ix2>, &Array2<bool>, &Array2<bool>, &FixedBitSet, &FixedBitSet),
+{
+
     //********************************************//
     //                                            //
     //                   Step 1                   //
     //                                            //
     //********************************************//
 
+    observe(1, m, stars, primes, row_cover, col_cover);
+
     // Reduce each row by its smallest element
-    for mut row in m.genrows_mut() {
-        let min = row.iter().min().unwrap().clone();
-        row.map_inplace(|v| *v -= min);
+    for (i, mut row) in m.genrows_mut().into_iter().enumerate() {
+        if let Some(a) = allowed {
+            let mask = a.row(i);
+            let min = row.iter()
+                .zip(mask.iter())
+                .filter(|&(_, &ok)| ok)
+                .map(|(&v, _)| v)
+                .min();
+            let min = match min {
+                Some(min) => min,
+                None => return false,
+            };
+            row.iter_mut()
+                .zip(mask.iter())
+                .filter(|&(_, &ok)| ok)
+                .for_each(|(v, _)| *v -= min);
+        } else {
+            #[cfg(feature = "simd")]
+            {
+                if let Some(row) = row.as_slice_mut() {
+                    let min = lane_min(row);
+                    row.iter_mut().for_each(|v| *v -= min);
+                    continue
+                }
+            }
+            let min = *row.iter().min().unwrap();
+            row.map_inplace(|v| *v -= min);
+        }
     }
 
+    solve_reduced_in(m, allowed, stars, primes, row_cover, col_cover, columns, observe)
+}
+
+/// Steps 2 through 6 of `solve_in`, for a workspace `m` whose rows have already
+/// been reduced: every row must have a zero among its allowed entries.
+#[allow(clippy::too_many_arguments)]
+fn solve_reduced_in<N, S, O>(
+    m: &mut ArrayBase<S, Ix2>,
+    allowed: Option<&Array2<bool>>,
+    stars: &mut Array2<bool>,
+    primes: &mut Array2<bool>,
+    row_cover: &mut FixedBitSet,
+    col_cover: &mut FixedBitSet,
+    columns: &mut Vec<usize>,
+    observe: &mut O,
+) -> bool
+    where N: NumAssign + Copy + Ord,
+          S: DataMut<Elem = N>,
+          O: FnMut(u8, &ArrayBase<S, Ix2>, &Array2<bool>, &Array2<bool>, &FixedBitSet, &FixedBitSet),
+{
+
+    let (h, w) = m.dim();
+
+    #[cfg(test)]
+    ITERATIONS.with(|n| n.set(0));
+
+    primes.fill(false);
+    row_cover.clear();
+    col_cover.clear();
+    columns.clear();
+
     //********************************************//
     //                                            //
     //                   Step 2                   //
     //                                            //
     //********************************************//
 
+    observe(2, m, stars, primes, row_cover, col_cover);
+
+    // Keep each seeded star that is still a valid starred zero.
+    // - Use row_cover and col_cover to keep track of stars.
+    let mut starred = 0;
+    for i in 0..h {
+        for j in 0..w {
+            if !get!(stars, i, j) { continue }
+            if on!(row_cover, i) || on!(col_cover, j) || !get!(m, i, j).is_zero() || !allowed!(allowed, i, j) {
+                set!(stars, i, j, false);
+                continue
+            }
+            row_cover.insert(i);
+            col_cover.insert(j);
+            starred += 1;
+        }
+    }
+
     // Find a zero (Z):
     // - If there is no starred zero in its row or column, then star it.
     // - Use col_cover to keep track of stars.
     for i in 0..h {
+        if on!(row_cover, i) { continue }
         for j in 0..w {
             if on!(col_cover, j) { continue }
-            if get!(m, i, j).is_zero() {
+            if get!(m, i, j).is_zero() && allowed!(allowed, i, j) {
                 set!(stars, i, j, true);
                 col_cover.insert(j);
+                starred += 1;
                 break
             }
         }
     }
 
+    // Every row already has a starred zero, so skip straight
+    // past [Step 3] without setting up the main loop.
+    if starred == h { return true }
+
     // Reset cover
+    row_cover.clear();
     col_cover.clear();
     let mut verify = true;
 
     loop {
 
+        #[cfg(test)]
+        ITERATIONS.with(|n| n.set(n.get() + 1));
+
         if verify {
 
             //********************************************//
@@ -234,6 +669,8 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
             //                                            //
             //********************************************//
 
+            observe(3, m, stars, primes, row_cover, col_cover);
+
             // Cover each column with a starred zero.
             stars.gencolumns()
                 .into_iter()
@@ -244,22 +681,7 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
 
             // If the number of starred zeros equals the number of rows, we're done.
             if col_cover.count_ones(..) == h {
-
-                let assign = stars.genrows().into_iter().map(|r| {
-                    r.iter().enumerate()
-                        .find(|&(_, &v)| v)
-                        .map(|(i, _)| i)
-                        .unwrap()
-                });
-
-                // Rotate results back if necessary
-                if rotated {
-                    let mut result = vec![None; w];
-                    assign.enumerate().for_each(|(i, j)| result[j] = Some(h - i - 1));
-                    return result
-                } else {
-                    return assign.map(|j| Some(j)).collect()
-                }
+                return true
             }
         }
 
@@ -269,23 +691,33 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
         //                                            //
         //********************************************//
 
+        observe(4, m, stars, primes, row_cover, col_cover);
+
         let mut uncovered = None;
 
-        // Find an uncovered zero and prime it
+        // Find an uncovered zero and prime it. If there is none, the scan
+        // has visited every uncovered entry, so keep track of their minimum
+        // for [Step 6] instead of scanning them all again there.
+        let mut min = None;
         'outer : for i in 0..h {
             if on!(row_cover, i) { continue }
             for j in 0..w {
-                if on!(col_cover, j) { continue }
-                if get!(m, i, j).is_zero() {
+                if on!(col_cover, j) || !allowed!(allowed, i, j) { continue }
+                let value = get!(m, i, j);
+                if value.is_zero() {
                     uncovered = Some((i, j));
                     set!(primes, i, j, true);
                     break 'outer;
                 }
+                min = match min {
+                    Some(min) if min <= value => Some(min),
+                    _ => Some(value),
+                };
             }
         }
 
         // No uncovered zeros left
-        if let None = uncovered {
+        if uncovered.is_none() {
 
             //********************************************//
             //                                            //
@@ -293,25 +725,51 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
             //                                            //
             //********************************************//
 
-            // Find minimum uncovered value
-            let mut min = N::max_value();
-            for i in 0..h {
-                if on!(row_cover, i) { continue }
-                for j in 0..w {
-                    if on!(col_cover, j) { continue }
-                    let value = get!(m, i, j);
-                    min = if value < min { value } else { min };
-                }
-            }
+            observe(6, m, stars, primes, row_cover, col_cover);
 
-            // Add minimum to covered rows
-            for i in (0..h).filter(|&i| on!(row_cover, i)) {
-                m.row_mut(i).map_inplace(|c| *c += min)
-            }
+            // Use the minimum uncovered value found in [Step 4]. If there is
+            // none, every uncovered entry is forbidden, so the covered lines form
+            // a vertex cover smaller than `h`: no complete matching exists.
+            let min = match min {
+                Some(min) => min,
+                None => return false,
+            };
 
-            // Subtract minimum from uncovered columns
-            for j in (0..w).filter(|&j| off!(col_cover, j)) {
-                m.column_mut(j).map_inplace(|c| *c -= min)
+            if let Some(a) = allowed {
+                // Add minimum to covered rows and subtract it from uncovered
+                // columns, leaving forbidden entries untouched
+                for i in 0..h {
+                    for j in 0..w {
+                        if !get!(a, i, j) { continue }
+                        let cost = get!(m, i, j);
+                        if on!(row_cover, i) && off!(col_cover, j) { continue }
+                        if on!(row_cover, i) { set!(m, i, j, cost + min) }
+                        if off!(col_cover, j) { set!(m, i, j, cost - min) }
+                    }
+                }
+            } else {
+                // Add minimum to covered rows and subtract it from uncovered
+                // columns, one row at a time: the two cancel out on covered
+                // rows at uncovered columns, so only entries where both or
+                // neither line is covered change.
+                columns.clear();
+                columns.extend((0..w).filter(|&j| on!(col_cover, j)));
+                let split = columns.len();
+                columns.extend((0..w).filter(|&j| off!(col_cover, j)));
+                let (covered, uncovered) = columns.split_at(split);
+                for i in 0..h {
+                    if on!(row_cover, i) {
+                        for &j in covered {
+                            let cost = get!(m, i, j);
+                            set!(m, i, j, cost + min);
+                        }
+                    } else {
+                        for &j in uncovered {
+                            let cost = get!(m, i, j);
+                            set!(m, i, j, cost - min);
+                        }
+                    }
+                }
             }
 
             // Return to [Step 4]
@@ -340,6 +798,8 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
         //                                            //
         //********************************************//
 
+        observe(5, m, stars, primes, row_cover, col_cover);
+
         // Construct an alternating path of stars and primes
         let mut path = vec![(i, j)];
         loop {
@@ -348,7 +808,7 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
             // Find starred zero in same column
             let next_star = (0..h).find(|&i| get!(stars, i, j));
 
-            if let None = next_star { break }
+            if next_star.is_none() { break }
             let i = next_star.unwrap();
             path.push((i, j));
 
@@ -361,7 +821,8 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
         // Unstar each starred zero
         // Star each primed zero
         for (i, j) in path {
-            set!(stars, i, j, *primes.uget((i, j)));
+            let prime = get!(primes, i, j);
+            set!(stars, i, j, prime);
         }
 
         // Reset cover
@@ -374,454 +835,9628 @@ pub fn minimize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usiz
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// Error returned when transforming a cost matrix would overflow the cost type.
+///
+/// Identifies the first entry (in row-major order) whose transformed
+/// cost cannot be represented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    /// Row of the offending entry
+    pub row: usize,
+    /// Column of the offending entry
+    pub col: usize,
+}
 
-    /// Internal macro for converting from a 2D index to a 1D index.
-    macro_rules! index {
-        ($w:expr, $i:expr, $j:expr) => (($w*$i) + $j)
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cost at ({}, {}) overflows when transformed", self.row, self.col)
     }
+}
 
-    use minimize;
+impl Error for OverflowError {}
 
-    #[test]
-    fn test_basic_0x0() {
-        let matrix: Vec<i32> = Vec::new();
-        assert_eq!(
-            minimize(&matrix, 0, 0),
-            Vec::new()
-        )
-    }
+/// Counterpart to [`minimize`](fn.minimize.html): finds a maximal matching such
+/// that the total cost (i.e. profit) is maximized.
+///
+/// Internally, each entry `v` is transformed into `max - v`, where `max` is
+/// the largest entry in the matrix, and the result is minimized. Every transformed
+/// entry lies between zero and `max - min`, so no clamping takes place and
+/// negative entries are handled correctly.
+///
+/// # Panics
+///
+/// Panics if the transformation overflows. See [`try_maximize`](fn.try_maximize.html)
+/// for which inputs are safe.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::maximize;
+///
+/// fn main() {
+///     let matrix = vec![
+///         3, 1, 2,
+///         2, 3, 1,
+///         1, 2, 3,
+///     ];
+///
+///     assert_eq!(maximize(&matrix, 3, 3), vec![Some(0), Some(1), Some(2)]);
+/// }
+/// ```
+#[must_use]
+pub fn maximize<N: NumAssign + PrimInt>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>> {
+    try_maximize(matrix, height, width)
+        .expect("maximize: cost range exceeds the cost type")
+}
 
-    #[test]
-    fn test_basic_1x1() {
-        let matrix = vec![1];
-        assert_eq!(
-            minimize(&matrix, 1, 1),
-            vec![Some(0)]
-        );
-    }
+/// Fallible variant of [`maximize`](fn.maximize.html).
+///
+/// The transformation `max - v` is safe for:
+///
+/// - Every matrix of an unsigned type, since `0 <= max - v <= max`.
+/// - Every matrix of a signed type where `max - min` fits in the type,
+///   e.g. every matrix whose entries are all non-negative, or all negative.
+///
+/// # Errors
+///
+/// Returns an `OverflowError` identifying the first entry `v` for which `max - v`
+/// cannot be represented. This is only possible for signed types.
+pub fn try_maximize<N>(matrix: &[N], height: usize, width: usize) -> Result<Vec<Option<usize>>, OverflowError>
+    where N: NumAssign + PrimInt
+{
+    let matrix = &matrix[..height * width];
+    let max = match matrix.iter().max() {
+        Some(&max) => max,
+        None => return Ok(minimize(matrix, height, width)),
+    };
 
-    #[test]
-    fn test_basic_1x2() {
-        let matrix = vec![
-            1, 2
-        ];
-        assert_eq!(
-            minimize(&matrix, 1, 2),
-            vec![Some(0)]
-        );
-    }
+    let costs = matrix.iter()
+        .enumerate()
+        .map(|(k, &v)| {
+            max.checked_sub(&v)
+                .ok_or(OverflowError { row: k / width, col: k % width })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    #[test]
-    fn test_basic_2x1() {
-        let matrix = vec![
-            1,
-            2
-        ];
-        assert_eq!(
-            minimize(&matrix, 2, 1),
-            vec![Some(0), None]
-        );
-    }
+    Ok(minimize(&costs, height, width))
+}
 
-    #[test]
-    fn test_basic_2x2() {
-        let matrix = vec![
-            1, 2,
-            2, 1,
-        ];
-        assert_eq!(
-            minimize(&matrix, 2, 2),
-            vec![Some(0), Some(1)]
-        );
+/// Variant of [`maximize`](fn.maximize.html) that forbids every entry with a
+/// profit below `floor`, leaving rows unassigned rather than matching them
+/// through barely profitable entries.
+///
+/// As many rows as possible are matched among the remaining entries, and the
+/// total profit is maximized among such matchings. The same gate is available
+/// on [`AssignmentProblem`](struct.AssignmentProblem.html) as `maximize().gate(floor)`.
+///
+/// # Panics
+///
+/// Panics if `max - v` overflows for an entry `v` at or above `floor`,
+/// where `max` is the largest such entry.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{maximize, maximize_floored};
+///
+/// fn main() {
+///     let matrix = vec![
+///         9, 8,
+///         7, 1,
+///     ];
+///
+///     assert_eq!(maximize(&matrix, 2, 2), vec![Some(1), Some(0)]);
+///     assert_eq!(maximize_floored(&matrix, 2, 2, 8), vec![Some(0), None]);
+/// }
+/// ```
+#[must_use]
+pub fn maximize_floored<N>(matrix: &[N], height: usize, width: usize, floor: N) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let matrix = &matrix[..height * width];
+    let max = match matrix.iter().filter(|&&v| v >= floor).max() {
+        Some(&max) => max,
+        None => return vec![None; height],
+    };
+
+    // Entries below the floor are forbidden, so their cost is never read
+    let costs = matrix.iter()
+        .map(|&v| if v >= floor {
+            max.checked_sub(&v).expect("maximize_floored: cost range exceeds the cost type")
+        } else {
+            N::zero()
+        })
+        .collect::<Vec<_>>();
+
+    solve_partial(height, width, |i, j| costs[width * i + j], |i, j| matrix[width * i + j] >= floor, false)
+}
+
+/// Error returned when the constraints of an assignment problem
+/// cannot be satisfied by any matching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InfeasibleError;
+
+impl fmt::Display for InfeasibleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no assignment satisfies the given constraints")
+    }
+}
+
+impl Error for InfeasibleError {}
+
+/// Variant of [`minimize`](fn.minimize.html) that guarantees every row
+/// in `required` is assigned.
+///
+/// When `width < height`, `minimize` leaves `height - width` rows unassigned,
+/// and chooses them purely by cost. This function instead only leaves
+/// rows outside of `required` unassigned, even if that raises the total cost.
+/// When `height <= width`, every row is assigned anyway, and this is
+/// equivalent to `minimize`.
+///
+/// # Errors
+///
+/// Returns an `InfeasibleError` if more rows are required than there are columns.
+///
+/// # Panics
+///
+/// Panics if any index in `required` is not less than `height`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_required};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1,  2,
+///         2,  1,
+///         9, 10,
+///     ];
+///
+///     assert_eq!(minimize(&matrix, 3, 2), vec![Some(0), Some(1), None]);
+///     assert_eq!(minimize_required(&matrix, 3, 2, &[2]), Ok(vec![None, Some(1), Some(0)]));
+/// }
+/// ```
+pub fn minimize_required<N>(matrix: &[N], height: usize, width: usize, required: &[usize]) -> Result<Vec<Option<usize>>, InfeasibleError>
+    where N: NumAssign + PrimInt
+{
+    let mut must = FixedBitSet::with_capacity(height);
+    for &i in required {
+        assert!(i < height, "required row {} out of bounds for height {}", i, height);
+        must.insert(i);
+    }
+
+    if height <= width { return Ok(minimize(matrix, height, width)) }
+    if must.count_ones(..) > width { return Err(InfeasibleError) }
+
+    // Pad with `height - width` zero-cost dummy columns, which only
+    // optional rows may use. Matching a row to a dummy leaves it unassigned.
+    let mut m = Array2::zeros((height, height));
+    let mut allowed = Array2::from_elem((height, height), true);
+
+    for i in 0..height {
+        for j in 0..width {
+            set!(m, i, j, clamp(&matrix[width * i + j]));
+        }
+        if on!(must, i) {
+            for j in width..height {
+                set!(allowed, i, j, false);
+            }
+        }
+    }
+
+    let stars = solve(&mut m, Some(&allowed)).ok_or(InfeasibleError)?;
+    Ok(assignment(&stars, false)
+        .into_iter()
+        .map(|j| j.filter(|&j| j < width))
+        .collect())
+}
+
+/// Renders `matrix` with the entries chosen by `assignment` in brackets,
+/// followed by the total cost of the assignment.
+///
+/// Columns are right-aligned to the widest entry, and rows left unassigned
+/// are marked as such. Intended for debugging and exploration, not for
+/// use in performance-sensitive code.
+///
+/// # Panics
+///
+/// Panics if `assignment` does not have exactly `height` entries, or
+/// assigns a row to a column outside of `0..width`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{format_assignment, minimize};
+///
+/// fn main() {
+///     let matrix = vec![
+///         5,  5,
+///         1, 10,
+///         2,  3,
+///     ];
+///
+///     let assignment = minimize(&matrix, 3, 2);
+///
+///     assert_eq!(
+///         format_assignment(&matrix, 3, 2, &assignment),
+///         concat!(
+///             "  5    5   (unassigned)\n",
+///             "[ 1]  10\n",
+///             "  2  [ 3]\n",
+///             "cost: 4\n",
+///         )
+///     );
+/// }
+/// ```
+pub fn format_assignment<N>(matrix: &[N], height: usize, width: usize, assignment: &[Option<usize>]) -> String
+    where N: PrimInt + fmt::Display
+{
+    assert_eq!(assignment.len(), height, "assignment must have one entry per row");
+
+    let cells = matrix.iter()
+        .take(height * width)
+        .map(|cost| cost.to_string())
+        .collect::<Vec<_>>();
+
+    let pad = cells.iter().map(|cell| cell.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    let mut cost = N::zero();
+
+    for i in 0..height {
+        let row = (0..width).map(|j| {
+            let cell = &cells[width * i + j];
+            if assignment[i] == Some(j) {
+                format!("[{:>pad$}]", cell, pad = pad)
+            } else {
+                format!(" {:>pad$} ", cell, pad = pad)
+            }
+        });
+        let mut line = row.collect::<Vec<_>>().join(" ");
+
+        if let Some(j) = assignment[i] {
+            assert!(j < width, "row {} assigned to column {} out of bounds", i, j);
+            cost = cost + matrix[width * i + j];
+        } else {
+            line.push_str("  (unassigned)");
+        }
+
+        // Unbracketed cells are padded on both sides, so trim the last one
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    out.push_str(&format!("cost: {}\n", cost));
+    out
+}
+
+/// Error returned when a nested matrix has rows of differing lengths.
+///
+/// Identifies the first row whose length differs from the length
+/// of the first row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RaggedMatrixError {
+    /// Index of the first offending row
+    pub row: usize,
+    /// Length of the first row
+    pub expected: usize,
+    /// Length of the offending row
+    pub found: usize,
+}
+
+impl fmt::Display for RaggedMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ragged matrix: row {} has length {}, expected {}",
+            self.row, self.found, self.expected,
+        )
+    }
+}
+
+impl Error for RaggedMatrixError {}
+
+/// Nested variant of [`minimize`](fn.minimize.html).
+///
+/// Accepts any slice of rows (e.g. `&[&[N]]` or `&[Vec<N>]`) instead of a
+/// flat slice with explicit dimensions. The height is the number of rows,
+/// and the width is the length of the first row.
+///
+/// # Errors
+///
+/// Returns a `RaggedMatrixError` identifying the first row whose length
+/// differs from the first row's.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_nested, RaggedMatrixError};
+///
+/// fn main() {
+///     let matrix = vec![
+///         vec![1, 2, 1],
+///         vec![4, 5, 6],
+///         vec![7, 8, 9],
+///     ];
+///
+///     assert_eq!(minimize_nested(&matrix), Ok(vec![Some(2), Some(1), Some(0)]));
+///
+///     let ragged = vec![
+///         vec![1, 2, 1],
+///         vec![4, 5],
+///     ];
+///
+///     assert_eq!(
+///         minimize_nested(&ragged),
+///         Err(RaggedMatrixError { row: 1, expected: 3, found: 2 })
+///     );
+/// }
+/// ```
+pub fn minimize_nested<N, R>(matrix: &[R]) -> Result<Vec<Option<usize>>, RaggedMatrixError>
+    where N: NumAssign + PrimInt,
+          R: AsRef<[N]>,
+{
+    let height = matrix.len();
+    let width = matrix.first().map_or(0, |row| row.as_ref().len());
+    let mut flat = Vec::with_capacity(height * width);
+
+    for (i, row) in matrix.iter().enumerate() {
+        let row = row.as_ref();
+        if row.len() != width {
+            return Err(RaggedMatrixError { row: i, expected: width, found: row.len() })
+        }
+        flat.extend_from_slice(row);
+    }
+
+    Ok(minimize(&flat, height, width))
+}
+
+/// Variant of [`minimize`](fn.minimize.html) where column `j` may be
+/// assigned to up to `col_capacity[j]` rows, while each row is still
+/// assigned to at most one column.
+///
+/// Internally, each column `j` is duplicated `col_capacity[j]` times and the
+/// expanded matrix is minimized, so this runs in `O(n^3)` time and `O(h*n)`
+/// space, where `n = max(height, sum(col_capacity))`. Capacities beyond
+/// `height` are never needed, and are truncated to `height`.
+///
+/// # Returns
+///
+/// - `v`: A Vec where `v[i]` is:
+///     - `Some(j)` if row `i` should be assigned to column `j`
+///     - `None` if row `i` is not in the optimal assignment. Only possible if
+///       the total capacity is less than `height`.
+///
+/// # Panics
+///
+/// Panics if `col_capacity` does not have exactly `width` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_capacities;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 5,
+///         2, 6,
+///         3, 4,
+///     ];
+///
+///     assert_eq!(
+///         minimize_with_capacities(&matrix, 3, 2, &[2, 1]),
+///         vec![Some(0), Some(0), Some(1)]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_capacities<N>(matrix: &[N], height: usize, width: usize, col_capacity: &[usize]) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(col_capacity.len(), width, "col_capacity must have one entry per column");
+
+    // Original column of each expanded column
+    let columns = col_capacity.iter()
+        .enumerate()
+        .flat_map(|(j, &c)| (0..c.min(height)).map(move |_| j))
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() { return vec![None; height] }
+
+    let expanded = columns.len();
+    let mut costs = Vec::with_capacity(height * expanded);
+    for i in 0..height {
+        costs.extend(columns.iter().map(|&j| matrix[width * i + j]));
+    }
+
+    minimize(&costs, height, expanded)
+        .into_iter()
+        .map(|j| j.map(|j| columns[j]))
+        .collect()
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that collapses exactly equal
+/// columns before solving, e.g. when columns stand for interchangeable tasks.
+///
+/// Each distinct column is kept once, with its number of copies as its capacity,
+/// and solved with [`minimize_with_capacities`](fn.minimize_with_capacities.html),
+/// which never needs more than `height` copies of any column. Rows assigned to a
+/// distinct column are then spread over its copies in increasing column order.
+/// The total cost is the same as `minimize`, though ties may be broken differently.
+///
+/// This only helps when many columns are identical in every row, and wide
+/// matrices have many more copies of a column than rows: otherwise the extra
+/// `O(height * width * log(width))` time spent sorting columns is wasted.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_dedup_columns;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 5, 1, 5, 1,
+///         2, 6, 2, 6, 2,
+///         3, 4, 3, 4, 3,
+///     ];
+///
+///     assert_eq!(
+///         minimize_dedup_columns(&matrix, 3, 5),
+///         vec![Some(0), Some(2), Some(4)]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_dedup_columns<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let column = |j: usize| (0..height).map(move |i| matrix[width * i + j]);
+
+    // Sorting is stable, so copies of a column stay in increasing order
+    let mut order = (0..width).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| column(a).cmp(column(b)));
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for j in order {
+        match groups.last_mut() {
+            Some(group) if column(group[0]).eq(column(j)) => group.push(j),
+            _ => groups.push(vec![j]),
+        }
+    }
+
+    let distinct = groups.len();
+    let mut costs = Vec::with_capacity(height * distinct);
+    for i in 0..height {
+        costs.extend(groups.iter().map(|group| matrix[width * i + group[0]]));
+    }
+
+    let capacities = groups.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut used = vec![0; distinct];
+    minimize_with_capacities(&costs, height, distinct, &capacities)
+        .into_iter()
+        .map(|g| g.map(|g| {
+            used[g] += 1;
+            groups[g][used[g] - 1]
+        }))
+        .collect()
+}
+
+/// Finds the single assignment minimizing the expected cost over several
+/// equally likely scenarios, each a `height * width` matrix in row-major order.
+///
+/// The expected cost of an assignment is its average cost over the scenarios,
+/// which is minimized by the same assignment as the total over the scenarios.
+/// So the matrices are summed entry by entry, and the sum is minimized. For
+/// scenarios of unequal probability, see
+/// [`minimize_expected_weighted`](fn.minimize_expected_weighted.html). As in
+/// `minimize`, the summed costs are clamped to be greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if a scenario doesn't have `height * width` entries, or if the sum
+/// overflows. See [`try_minimize_expected`](fn.try_minimize_expected.html).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_expected};
+///
+/// fn main() {
+///     let sunny = [1, 9, 9, 1];
+///     let rainy = [9, 2, 2, 9];
+///
+///     // Each scenario alone prefers a different assignment
+///     assert_eq!(minimize(&sunny, 2, 2), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize(&rainy, 2, 2), vec![Some(1), Some(0)]);
+///
+///     assert_eq!(minimize_expected(&[&sunny, &rainy], 2, 2), vec![Some(0), Some(1)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_expected<N>(matrices: &[&[N]], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    try_minimize_expected(matrices, height, width)
+        .expect("minimize_expected: sum of scenarios exceeds the cost type")
+}
+
+/// Fallible variant of [`minimize_expected`](fn.minimize_expected.html).
+///
+/// # Errors
+///
+/// Returns an `OverflowError` identifying the first entry whose sum over
+/// the scenarios can't be represented.
+///
+/// # Panics
+///
+/// Panics if a scenario doesn't have `height * width` entries.
+pub fn try_minimize_expected<N>(matrices: &[&[N]], height: usize, width: usize) -> Result<Vec<Option<usize>>, OverflowError>
+    where N: NumAssign + PrimInt
+{
+    try_minimize_expected_weighted(matrices, &vec![N::one(); matrices.len()], height, width)
+}
+
+/// Variant of [`minimize_expected`](fn.minimize_expected.html) for scenarios of
+/// unequal probability, where `weights[s]` is the relative weight of `matrices[s]`.
+///
+/// The matrices are scaled by their weights and summed entry by entry, which
+/// gives the weighted expected cost of every entry, up to a common factor. So
+/// weights only need to be proportional to the probabilities, e.g. percentages
+/// or counts of observations. As in `minimize`, the summed costs are clamped to
+/// be greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if `weights` doesn't have one entry per scenario, if a scenario doesn't
+/// have `height * width` entries, or if the weighted sum overflows. See
+/// [`try_minimize_expected_weighted`](fn.try_minimize_expected_weighted.html).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_expected_weighted;
+///
+/// fn main() {
+///     let sunny = [1, 9, 9, 1];
+///     let rainy = [9, 2, 2, 9];
+///
+///     assert_eq!(minimize_expected_weighted(&[&sunny, &rainy], &[50, 50], 2, 2), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize_expected_weighted(&[&sunny, &rainy], &[10, 90], 2, 2), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_expected_weighted<N>(matrices: &[&[N]], weights: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    try_minimize_expected_weighted(matrices, weights, height, width)
+        .expect("minimize_expected_weighted: weighted sum of scenarios exceeds the cost type")
+}
+
+/// Fallible variant of [`minimize_expected_weighted`](fn.minimize_expected_weighted.html).
+///
+/// # Errors
+///
+/// Returns an `OverflowError` identifying the first entry whose weighted sum
+/// over the scenarios can't be represented.
+///
+/// # Panics
+///
+/// Panics if `weights` doesn't have one entry per scenario, or if a scenario
+/// doesn't have `height * width` entries.
+pub fn try_minimize_expected_weighted<N>(
+    matrices: &[&[N]],
+    weights: &[N],
+    height: usize,
+    width: usize,
+) -> Result<Vec<Option<usize>>, OverflowError>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(weights.len(), matrices.len(), "weights must have one entry per scenario");
+
+    let mut total = vec![N::zero(); height * width];
+    for (s, (matrix, &weight)) in matrices.iter().zip(weights).enumerate() {
+        assert_eq!(matrix.len(), height * width, "scenario {} has the wrong number of entries", s);
+        for (k, (sum, &v)) in total.iter_mut().zip(matrix.iter()).enumerate() {
+            *sum = v.checked_mul(&weight)
+                .and_then(|v| sum.checked_add(&v))
+                .ok_or(OverflowError { row: k / width, col: k % width })?;
+        }
+    }
+    Ok(minimize(&total, height, width))
+}
+
+/// Converts an assignment into a list of `(row, column, cost)` edges,
+/// with costs looked up in the original `matrix`.
+///
+/// Rows left unassigned (i.e. `None`) contribute no edge, and edges
+/// are listed in increasing row order. This is convenient for feeding
+/// assignments into graph libraries or visualization tools.
+///
+/// # Panics
+///
+/// Panics if `assignment` assigns a row to a column outside of `0..width`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{assignment_edges, minimize};
+///
+/// fn main() {
+///     let matrix = vec![
+///         5, 5,
+///         1, 0,
+///         2, 3,
+///     ];
+///
+///     let assignment = minimize(&matrix, 3, 2);
+///
+///     assert_eq!(
+///         assignment_edges(&matrix, 3, 2, &assignment),
+///         vec![(1, 1, 0), (2, 0, 2)]
+///     );
+/// }
+/// ```
+pub fn assignment_edges<N: Copy>(matrix: &[N], height: usize, width: usize, assignment: &[Option<usize>]) -> Vec<(usize, usize, N)> {
+    assignment.iter()
+        .take(height)
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| {
+            assert!(j < width, "row {} assigned to column {} out of bounds", i, j);
+            (i, j, matrix[width * i + j])
+        }))
+        .collect()
+}
+
+/// Converts an assignment into an undirected bipartite `petgraph` graph.
+///
+/// Row `i` becomes node `i`, and column `j` becomes node `height + j`,
+/// so every row and column is present even if unassigned. Each assigned
+/// row is connected to its column by an edge weighted with its original cost.
+///
+/// Requires the `petgraph` feature.
+#[cfg(feature = "petgraph")]
+pub fn assignment_graph<N: Copy>(matrix: &[N], height: usize, width: usize, assignment: &[Option<usize>]) -> petgraph::graph::UnGraph<(), N> {
+    let mut graph = petgraph::graph::UnGraph::with_capacity(height + width, height.min(width));
+    for _ in 0..(height + width) {
+        graph.add_node(());
+    }
+    for (i, j, cost) in assignment_edges(matrix, height, width, assignment) {
+        graph.add_edge(
+            petgraph::graph::NodeIndex::new(i),
+            petgraph::graph::NodeIndex::new(height + j),
+            cost,
+        );
+    }
+    graph
+}
+
+/// Error returned when a flat matrix doesn't match its stated dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DimensionError {
+    /// The matrix has `found` entries, but `height * width == expected`.
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The matrix has `found` entries, but `height` or `width` is zero.
+    ZeroDimension {
+        height: usize,
+        width: usize,
+        found: usize,
+    },
+    /// `height * width` overflows `usize`, e.g. on a 32-bit target.
+    Overflow {
+        height: usize,
+        width: usize,
+    },
+}
+
+impl fmt::Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+        DimensionError::LengthMismatch { expected, found } => {
+            write!(f, "matrix has {} entries, expected {}", found, expected)
+        }
+        DimensionError::ZeroDimension { height, width, found } => {
+            write!(f, "matrix has {} entries, but dimensions are {}x{}", found, height, width)
+        }
+        DimensionError::Overflow { height, width } => {
+            write!(f, "dimensions {}x{} overflow usize", height, width)
+        }
+        }
+    }
+}
+
+impl Error for DimensionError {}
+
+/// Fallible variant of [`minimize`](fn.minimize.html), which validates
+/// `matrix` against `height` and `width` before solving.
+///
+/// A matrix with zero rows or columns and no entries is legitimately empty,
+/// and has an empty assignment.
+///
+/// # Errors
+///
+/// - `DimensionError::ZeroDimension` if `height` or `width` is zero, but `matrix` isn't empty.
+/// - `DimensionError::Overflow` if `height * width` overflows `usize`.
+/// - `DimensionError::LengthMismatch` if `matrix.len() != height * width` otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{try_minimize, DimensionError};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 1,
+///     ];
+///
+///     assert_eq!(try_minimize(&matrix, 2, 2), Ok(vec![Some(0), Some(1)]));
+///
+///     assert_eq!(
+///         try_minimize(&matrix, 2, 3),
+///         Err(DimensionError::LengthMismatch { expected: 6, found: 4 })
+///     );
+/// }
+/// ```
+pub fn try_minimize<N>(matrix: &[N], height: usize, width: usize) -> Result<Vec<Option<usize>>, DimensionError>
+    where N: NumAssign + PrimInt
+{
+    check_dimensions(matrix.len(), height, width)?;
+    Ok(minimize(matrix, height, width))
+}
+
+/// Internal helper to validate the length of a flat matrix against its dimensions.
+fn check_dimensions(found: usize, height: usize, width: usize) -> Result<(), DimensionError> {
+
+    if height == 0 || width == 0 {
+        return if found == 0 {
+            Ok(())
+        } else {
+            Err(DimensionError::ZeroDimension { height, width, found })
+        }
+    }
+
+    let expected = height.checked_mul(width).ok_or(DimensionError::Overflow { height, width })?;
+    if found != expected {
+        return Err(DimensionError::LengthMismatch { expected, found })
+    }
+
+    Ok(())
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that solves the assignment problem
+/// on a rectangular window of a larger matrix, without copying the window out first.
+///
+/// # Takes
+///
+/// - `matrix`: a 1D slice in row-major order, representing the full 2D matrix
+/// - `full_width`: width of the full `matrix` (i.e. number of columns)
+/// - `row_range`: rows of `matrix` included in the window
+/// - `col_range`: columns of `matrix` included in the window
+///
+/// # Returns
+///
+/// - `v`: A Vec where `v[k]` corresponds to row `row_range.start + k`, and is:
+///     - `Some(j)` if the row should be assigned to column `j`, where `j` is
+///       an index into the full `matrix` (i.e. `j` lies within `col_range`)
+///     - `None` if the row is not in the optimal assignment
+///
+/// # Panics
+///
+/// Panics if `col_range` extends past `full_width`, or `row_range`
+/// extends past the end of `matrix`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_window;
+///
+/// fn main() {
+///     let matrix = vec![
+///         9, 9, 9, 9,
+///         9, 1, 2, 9,
+///         9, 2, 1, 9,
+///     ];
+///
+///     assert_eq!(minimize_window(&matrix, 4, 1..3, 1..3), vec![Some(1), Some(2)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_window<N>(matrix: &[N], full_width: usize, row_range: Range<usize>, col_range: Range<usize>) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert!(col_range.end <= full_width, "column window {:?} out of bounds for width {}", col_range, full_width);
+    assert!(
+        matches!(row_range.end.checked_mul(full_width), Some(n) if n <= matrix.len()),
+        "row window {:?} out of bounds",
+        row_range,
+    );
+
+    let height = row_range.len();
+    let width = col_range.len();
+
+    // No possible assignment
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    let (top, left) = (row_range.start, col_range.start);
+    let mut m = orient(height, width, |i, j| {
+        clamp(&matrix[full_width * (top + i) + left + j])
+    });
+
+    let stars = solve(&mut m, None).unwrap();
+    assignment(&stars, width < height)
+        .into_iter()
+        .map(|j| j.map(|j| j + left))
+        .collect()
+}
+
+/// Internal SplitMix64 generator, used to derive deterministic permutations
+/// without pulling in a dependency on `rand`.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Internal Fisher-Yates shuffle of `0..n`, driven by `state`.
+fn permutation(n: usize, state: &mut u64) -> Vec<usize> {
+    let mut perm = (0..n).collect::<Vec<_>>();
+    for i in (1..n).rev() {
+        let j = (splitmix64(state) % (i as u64 + 1)) as usize;
+        perm.swap(i, j);
+    }
+    perm
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that solves on a deterministically
+/// shuffled copy of the matrix, and maps the result back.
+///
+/// The rows and columns are permuted by a Fisher-Yates shuffle seeded with `seed`,
+/// so the same `seed` always produces the same result. The result is optimal, but
+/// when there are ties it may differ from the assignment chosen by `minimize`.
+///
+/// The main loop scans rows and columns in index order: Step 2 stars the first zero
+/// of each row, and Step 4 searches for an uncovered zero from the first row. Inputs
+/// whose structure lines up with that order can drive both down their slowest paths,
+/// such as the `staircase_matrix` fixture, where starring first zeros leaves half of
+/// the rows unmatched and every search scans past the rows that are already settled.
+/// Shuffling breaks up such structure, at the cost of an extra pass over the matrix:
+/// it takes about a quarter of the passes through the main loop on that family, and
+/// the `hungarian_shuffled_staircase_NxN` benchmark is several times faster than
+/// `hungarian_staircase_NxN`.
+///
+/// Note that this doesn't help on every hard input: the `(i + 1) * (j + 1)` family
+/// from the benchmarks is just as hard in any order (permuting its rows and columns
+/// only permutes the factors), and the `hungarian_shuffled_worst_case_NxN`
+/// benchmark is slightly slower than `hungarian_worst_case_NxN`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_shuffled};
+///
+/// fn main() {
+///     let matrix = vec![
+///         250, 400, 350,
+///         400, 600, 350,
+///         200, 400, 250,
+///     ];
+///
+///     assert_eq!(minimize_shuffled(&matrix, 3, 3, 42), minimize(&matrix, 3, 3));
+/// }
+/// ```
+#[must_use]
+pub fn minimize_shuffled<N>(matrix: &[N], height: usize, width: usize, seed: u64) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    // No possible assignment
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    let mut state = seed;
+    let rows = permutation(height, &mut state);
+    let cols = permutation(width, &mut state);
+
+    let mut m = orient(height, width, |i, j| {
+        clamp(&matrix[width * rows[i] + cols[j]])
+    });
+
+    let stars = solve(&mut m, None).unwrap();
+    let mut result = vec![None; height];
+    for (i, j) in assignment(&stars, width < height).into_iter().enumerate() {
+        result[rows[i]] = j.map(|j| cols[j]);
+    }
+    result
+}
+
+/// Internal helper to sum the (clamped) costs of the entries chosen by `assignment`.
+fn total_cost<N: NumAssign + PrimInt>(matrix: &[N], width: usize, assignment: &[Option<usize>]) -> N {
+    assignment.iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| clamp(&matrix[width * i + j])))
+        .fold(N::zero(), |acc, cost| acc + cost)
+}
+
+/// Checks whether an externally-provided `assignment` is an optimal solution
+/// for `matrix`, i.e. whether it's something [`minimize`](fn.minimize.html)
+/// could have returned.
+///
+/// An assignment is optimal if:
+///
+/// - It has exactly `height` entries.
+/// - It's a matching: every column is in bounds, and no column is used twice.
+/// - It's maximal: exactly `min(height, width)` rows are assigned.
+/// - Its total cost equals the optimal cost, with negative entries
+///   clamped to zero as in `minimize`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::is_optimal;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 1,
+///     ];
+///
+///     assert!(is_optimal(&matrix, 2, 2, &[Some(0), Some(1)]));
+///     assert!(!is_optimal(&matrix, 2, 2, &[Some(1), Some(0)]));
+///     assert!(!is_optimal(&matrix, 2, 2, &[Some(0), Some(0)]));
+/// }
+/// ```
+pub fn is_optimal<N>(matrix: &[N], height: usize, width: usize, assignment: &[Option<usize>]) -> bool
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 {
+        return assignment.len() == height && assignment.iter().all(Option::is_none)
+    }
+
+    if assignment.len() != height { return false }
+
+    let mut used = FixedBitSet::with_capacity(width);
+    for &j in assignment.iter().filter_map(Option::as_ref) {
+        if j >= width || on!(used, j) { return false }
+        used.insert(j);
+    }
+
+    if used.count_ones(..) != height.min(width) { return false }
+
+    let optimal = minimize(matrix, height, width);
+    total_cost(matrix, width, assignment) == total_cost(matrix, width, &optimal)
+}
+
+/// Computes the reduced cost matrix at the optimum of [`minimize`](fn.minimize.html).
+///
+/// Entry `(i, j)` is the original cost minus the row potential of `i` and the
+/// column potential of `j`, where the potentials form an optimal dual solution.
+/// Every reduced cost is non-negative, and every edge in the optimal assignment
+/// has a reduced cost of zero, which certifies its optimality. As in `minimize`,
+/// negative costs are clamped to zero first.
+///
+/// Returns a `height * width` matrix in the original orientation.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+/// extern crate ndarray;
+///
+/// use hungarian::reduced_cost_matrix;
+/// use ndarray::arr2;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         3, 5,
+///     ];
+///
+///     assert_eq!(reduced_cost_matrix(&matrix, 2, 2), arr2(&[[0, 0], [0, 1]]));
+/// }
+/// ```
+pub fn reduced_cost_matrix<N>(matrix: &[N], height: usize, width: usize) -> Array2<N>
+    where N: NumAssign + PrimInt
+{
+    // No possible assignment
+    if height == 0 || width == 0 { return Array2::zeros((height, width)) }
+
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    solve(&mut m, None);
+    unorient(&m, height, width)
+}
+
+/// Checks whether `row_pot` and `col_pot` are dual potentials certifying that
+/// `assignment` is optimal for `matrix`, e.g. to validate a dual solution
+/// computed elsewhere.
+///
+/// The reduced cost of entry `(i, j)` is `matrix[i][j] - row_pot[i] - col_pot[j]`,
+/// with negative costs clamped to zero as in [`minimize`](fn.minimize.html).
+/// The certificate holds if:
+///
+/// - `assignment` is a maximal matching, as in [`is_optimal`](fn.is_optimal.html),
+///   and the potentials have lengths `height` and `width`.
+/// - Every reduced cost is non-negative, and every assigned entry's is zero.
+/// - For rectangular matrices, the side with unassigned entries has potentials
+///   that are all non-positive, and zero wherever unassigned. That is, if
+///   `height < width`, every column potential is at most zero, and exactly zero
+///   for every unassigned column (likewise for rows if `height > width`).
+///
+/// Together, these prove that no other maximal matching is cheaper. Returns
+/// `false` if computing a reduced cost overflows.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::verify_potentials;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         3, 5,
+///     ];
+///
+///     let assignment = [Some(1), Some(0)];
+///     assert!(verify_potentials(&matrix, 2, 2, &[1, 3], &[0, 1], &assignment));
+///     assert!(!verify_potentials(&matrix, 2, 2, &[1, 3], &[0, 2], &assignment));
+/// }
+/// ```
+pub fn verify_potentials<N>(matrix: &[N], height: usize, width: usize, row_pot: &[N], col_pot: &[N], assignment: &[Option<usize>]) -> bool
+    where N: NumAssign + PrimInt
+{
+    if row_pot.len() != height || col_pot.len() != width || assignment.len() != height {
+        return false
+    }
+
+    let mut used = FixedBitSet::with_capacity(width);
+    for &j in assignment.iter().filter_map(Option::as_ref) {
+        if j >= width || on!(used, j) { return false }
+        used.insert(j);
+    }
+
+    if used.count_ones(..) != height.min(width) { return false }
+
+    // Potentials on the side with slack must be non-positive, and zero where unused
+    let slack = |potential: N, assigned: bool| {
+        potential <= N::zero() && (assigned || potential.is_zero())
+    };
+
+    if height < width && !(0..width).all(|j| slack(col_pot[j], on!(used, j))) {
+        return false
+    }
+
+    if width < height && !(0..height).all(|i| slack(row_pot[i], assignment[i].is_some())) {
+        return false
+    }
+
+    for i in 0..height {
+        for j in 0..width {
+            let reduced = match clamp(&matrix[width * i + j])
+                .checked_sub(&row_pot[i])
+                .and_then(|cost| cost.checked_sub(&col_pot[j])) {
+                Some(reduced) => reduced,
+                None => return false,
+            };
+
+            if reduced < N::zero() || (assignment[i] == Some(j) && !reduced.is_zero()) {
+                return false
+            }
+        }
+    }
+
+    true
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also returns optimal dual potentials,
+/// as `(assignment, row_pot, col_pot)`, certifying the assignment's optimality.
+///
+/// The potentials are in the original orientation: `row_pot` has `height` entries
+/// and `col_pot` has `width`, whichever side is longer, and they satisfy every
+/// condition of [`verify_potentials`](fn.verify_potentials.html) in terms of the
+/// caller's indices. In particular, the reduced cost `c[i][j] - row_pot[i] - col_pot[j]`
+/// is non-negative everywhere and zero on every assigned entry, and on the longer
+/// side, potentials are non-positive and zero wherever unassigned.
+///
+/// Rather than transposing a tall matrix as `minimize` does, this pads a rectangular
+/// one into a square one with zero cost dummy rows or columns, and reads the
+/// potentials off its reduced costs. The dummies are interchangeable, so an optimal
+/// dual gives them all the same potential, and shifting it to zero yields the slack
+/// conditions above. Potentials can be negative, so this requires a signed cost
+/// type. As in `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_with_potentials, verify_potentials};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 4,
+///         5, 6,
+///         3, 2,
+///     ];
+///
+///     let (assignment, row_pot, col_pot) = minimize_with_potentials(&matrix, 3, 2);
+///     assert_eq!(assignment, vec![Some(0), None, Some(1)]);
+///     assert_eq!((row_pot.len(), col_pot.len()), (3, 2));
+///     assert!(verify_potentials(&matrix, 3, 2, &row_pot, &col_pot, &assignment));
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_potentials<N>(matrix: &[N], height: usize, width: usize) -> (Vec<Option<usize>>, Vec<N>, Vec<N>)
+    where N: NumAssign + PrimInt + Signed
+{
+    if height == 0 || width == 0 {
+        return (vec![None; height], vec![N::zero(); height], vec![N::zero(); width])
+    }
+
+    let n = height.max(width);
+    let cost = |i: usize, j: usize| {
+        if i < height && j < width { clamp(&matrix[width * i + j]) } else { N::zero() }
+    };
+
+    let mut m = Array2::from_shape_fn((n, n), |(i, j)| cost(i, j));
+    let stars = solve(&mut m, None).unwrap();
+
+    // Each step of the algorithm adds a constant to a row or column, so the reduced
+    // costs are `c[i][j] - u[i] - v[j]` for some `u` and `v`: fix `u[0]` at zero
+    let mut col_pot = (0..n).map(|j| cost(0, j) - get!(m, 0, j)).collect::<Vec<_>>();
+    let mut row_pot = (0..n).map(|i| cost(i, 0) - get!(m, i, 0) - col_pot[0]).collect::<Vec<_>>();
+
+    // Shift the dummies' shared potential to zero
+    let shift = if height < width { row_pot[n - 1] } else { -col_pot[n - 1] };
+    row_pot.iter_mut().for_each(|u| *u -= shift);
+    col_pot.iter_mut().for_each(|v| *v += shift);
+    row_pot.truncate(height);
+    col_pot.truncate(width);
+
+    let assignment = (0..height)
+        .map(|i| (0..width).find(|&j| get!(stars, i, j)))
+        .collect();
+
+    (assignment, row_pot, col_pot)
+}
+
+/// Non-generic [`minimize`](fn.minimize.html) for `u32` costs.
+///
+/// `minimize` is generic, so it's monomorphized for each cost type and
+/// there's no dynamic dispatch involved: for a given cost type, it compiles
+/// to the same code as this function. The difference is where that code is
+/// compiled. Generic functions are instantiated in the calling crate, under the
+/// caller's optimization settings, whereas this wrapper is compiled once here.
+/// It exists for callers who want predictable codegen for the most common
+/// cost type (e.g. when building dependencies with a different profile).
+///
+/// The `hungarian_u32_NxN` benchmark compares both paths at `N = 500`, and
+/// shows no measurable difference between them.
+#[must_use]
+pub fn minimize_u32(matrix: &[u32], height: usize, width: usize) -> Vec<Option<usize>> {
+    minimize(matrix, height, width)
+}
+
+/// Non-generic [`minimize`](fn.minimize.html) for `usize` costs.
+///
+/// See [`minimize_u32`](fn.minimize_u32.html) for why this exists.
+#[must_use]
+pub fn minimize_usize(matrix: &[usize], height: usize, width: usize) -> Vec<Option<usize>> {
+    minimize(matrix, height, width)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for small square matrices whose size
+/// is known at compile time, which never allocates on the heap.
+///
+/// Runs the same steps as `minimize`, but every workspace lives on the stack:
+/// the reduced costs in a copy of the matrix, the starred and primed zeros as one
+/// column index per row, and the row and column covers as `u64` bit masks. This
+/// makes it usable in hard real-time or embedded code, for `N` up to 64. The
+/// result maps each row to its column. As in `minimize`, costs are clamped to be
+/// greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if `N > 64`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_fixed;
+///
+/// fn main() {
+///     let matrix = [
+///         [1, 2, 1],
+///         [4, 5, 6],
+///         [7, 8, 9],
+///     ];
+///
+///     assert_eq!(minimize_fixed(&matrix), [2, 1, 0]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_fixed<T, const N: usize>(matrix: &[[T; N]; N]) -> [usize; N]
+    where T: NumAssign + PrimInt
+{
+    assert!(N <= 64, "minimize_fixed supports at most 64 rows, found {}", N);
+
+    // Marks a row or column without a starred or primed zero
+    const NONE: usize = usize::MAX;
+
+    let mut m = [[T::zero(); N]; N];
+    for (row, costs) in m.iter_mut().zip(matrix) {
+        for (cost, value) in row.iter_mut().zip(costs) {
+            *cost = clamp(value);
+        }
+    }
+
+    // Step 1: reduce each row by its smallest element
+    for row in m.iter_mut() {
+        let min = row.iter().fold(T::max_value(), |min, &cost| min.min(cost));
+        row.iter_mut().for_each(|cost| *cost -= min);
+    }
+
+    // Step 2: greedily star zeros with no other star in their row or column
+    let mut star_in_row = [NONE; N];
+    let mut star_in_col = [NONE; N];
+    for i in 0..N {
+        if let Some(j) = (0..N).find(|&j| m[i][j].is_zero() && star_in_col[j] == NONE) {
+            star_in_row[i] = j;
+            star_in_col[j] = i;
+        }
+    }
+
+    let bit = |k: usize| 1u64 << k;
+    let mut prime_in_row = [NONE; N];
+
+    loop {
+
+        // Step 3: cover each column with a starred zero
+        let mut row_cover = 0u64;
+        let mut col_cover = (0..N)
+            .filter(|&j| star_in_col[j] != NONE)
+            .fold(0u64, |cover, j| cover | bit(j));
+
+        if col_cover.count_ones() as usize == N { return star_in_row }
+
+        // Step 4: prime uncovered zeros until one has no starred zero in its row
+        let (mut i, mut j) = loop {
+            let mut uncovered = None;
+            let mut min = None;
+            'outer: for i in (0..N).filter(|&i| row_cover & bit(i) == 0) {
+                for j in (0..N).filter(|&j| col_cover & bit(j) == 0) {
+                    if m[i][j].is_zero() {
+                        uncovered = Some((i, j));
+                        break 'outer
+                    }
+                    min = match min {
+                        Some(min) if min <= m[i][j] => Some(min),
+                        _ => Some(m[i][j]),
+                    };
+                }
+            }
+
+            match uncovered {
+                Some((i, j)) => {
+                    prime_in_row[i] = j;
+                    if star_in_row[i] == NONE { break (i, j) }
+                    row_cover |= bit(i);
+                    col_cover &= !bit(star_in_row[i]);
+                }
+                None => {
+                    // Step 6: add the minimum to covered rows, and subtract
+                    // it from uncovered columns
+                    let min = min.expect("a square matrix always has an uncovered entry");
+                    for (i, row) in m.iter_mut().enumerate() {
+                        for (j, cost) in row.iter_mut().enumerate() {
+                            match (row_cover & bit(i) != 0, col_cover & bit(j) != 0) {
+                                (true, true) => *cost += min,
+                                (false, false) => *cost -= min,
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        // Step 5: star each primed zero along the alternating path, which
+        // unstars each starred zero along it
+        loop {
+            let next = star_in_col[j];
+            star_in_row[i] = j;
+            star_in_col[j] = i;
+            if next == NONE { break }
+            i = next;
+            j = prime_in_row[i];
+        }
+
+        prime_in_row = [NONE; N];
+    }
+}
+
+/// Internal helper for Kuhn's algorithm: tries to match row `i` along an
+/// augmenting path, where `owner[j]` is the row currently matched to column `j`.
+fn augment(i: usize, adjacent: &[Vec<usize>], seen: &mut FixedBitSet, owner: &mut [Option<usize>]) -> bool {
+    for &j in &adjacent[i] {
+        if on!(seen, j) { continue }
+        seen.insert(j);
+        let free = match owner[j] {
+            Some(k) => augment(k, adjacent, seen, owner),
+            None => true,
+        };
+        if free {
+            owner[j] = Some(i);
+            return true
+        }
+    }
+    false
+}
+
+/// Internal maximum cardinality bipartite matching, ignoring costs.
+///
+/// Runs Kuhn's algorithm in `O(h * e)` time over the `e` allowed entries,
+/// where `adjacent[i]` lists the columns row `i` may be matched to.
+fn max_matching(adjacent: &[Vec<usize>], width: usize) -> Vec<Option<usize>> {
+    let mut owner = vec![None; width];
+    let mut seen = FixedBitSet::with_capacity(width);
+    for i in 0..adjacent.len() {
+        seen.clear();
+        augment(i, adjacent, &mut seen, &mut owner);
+    }
+
+    let mut result = vec![None; adjacent.len()];
+    for (j, i) in owner.into_iter().enumerate() {
+        if let Some(i) = i { result[i] = Some(j) }
+    }
+    result
+}
+
+/// Internal helper for `lexicographic`: moves row `p` off of its column onto
+/// another tight, unvisited column, recursively displacing that column's row,
+/// until the displaced row lands on `target`.
+#[allow(clippy::too_many_arguments)]
+fn reroute(
+    p: usize,
+    target: usize,
+    tight: &Array2<bool>,
+    fixed: &FixedBitSet,
+    seen: &mut FixedBitSet,
+    col_of: &mut [usize],
+    row_of: &mut [usize],
+) -> bool {
+    let n = col_of.len();
+    for c in 0..n {
+        if !get!(tight, p, c) || on!(seen, c) { continue }
+        seen.insert(c);
+        let q = row_of[c];
+        if c == target || (off!(fixed, q) && reroute(q, target, tight, fixed, seen, col_of, row_of)) {
+            col_of[p] = c;
+            row_of[c] = p;
+            return true
+        }
+    }
+    false
+}
+
+/// Internal tie-breaker: among all optimal perfect matchings of the square
+/// workspace `m`, finds the one where the first `rows` rows are matched to the
+/// lowest possible columns, compared row by row.
+///
+/// `m` must hold the reduced costs at the optimum, as left by `solve`, and `stars`
+/// an optimal perfect matching. Every optimal matching only uses allowed entries
+/// with zero reduced cost, so this only needs to search those. For each row, each
+/// candidate column costs one `O(n^2)` search for an alternating path, so this takes
+/// `O(n^4)` time in the worst case, when every entry is tied.
+fn lexicographic<N>(m: &Array2<N>, allowed: Option<&Array2<bool>>, stars: &Array2<bool>, rows: usize) -> Vec<usize>
+    where N: NumAssign + PrimInt
+{
+    let n = m.dim().0;
+    let tight = Array2::from_shape_fn((n, n), |(i, j)| {
+        get!(m, i, j).is_zero() && allowed!(allowed, i, j)
+    });
+
+    let mut col_of = vec![0; n];
+    let mut row_of = vec![0; n];
+    for (i, col) in col_of.iter_mut().enumerate() {
+        let j = (0..n).find(|&j| get!(stars, i, j)).unwrap();
+        *col = j;
+        row_of[j] = i;
+    }
+
+    let mut fixed = FixedBitSet::with_capacity(n);
+    let mut seen = FixedBitSet::with_capacity(n);
+
+    for i in 0..rows {
+        let target = col_of[i];
+        fixed.insert(i);
+        for c in (0..target).filter(|&c| get!(tight, i, c)) {
+            let p = row_of[c];
+            if on!(fixed, p) { continue }
+
+            // Row `i` takes column `c` if its owner can be moved
+            // along an alternating path onto column `target`.
+            seen.clear();
+            seen.insert(c);
+            if reroute(p, target, &tight, &fixed, &mut seen, &mut col_of, &mut row_of) {
+                col_of[i] = c;
+                row_of[c] = i;
+                break
+            }
+        }
+    }
+
+    col_of
+}
+
+/// Internal solver for problems with forbidden entries, where rows may be left unassigned.
+///
+/// Finds a matching of maximum cardinality among the entries where `allowed(i, j)`
+/// holds, and of minimum total cost among those. `cost(i, j)` must be non-negative.
+/// If `stable` is set, ties are broken in favor of assigning lower rows to lower
+/// columns (see `lexicographic`), rather than by iteration order.
+///
+/// The problem is padded into a square one, which can always be perfectly matched:
+/// if at most `r` rows can be matched, there are `width - r` dummy rows and
+/// `height - r` dummy columns of zero cost, and dummy rows may not be matched to
+/// dummy columns. Exactly `r` original rows are then matched to original columns.
+fn solve_partial<N, C, A>(height: usize, width: usize, cost: C, allowed: A, stable: bool) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt,
+          C: Fn(usize, usize) -> N,
+          A: Fn(usize, usize) -> bool,
+{
+    let adjacent = (0..height)
+        .map(|i| (0..width).filter(|&j| allowed(i, j)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let r = max_matching(&adjacent, width).iter().filter(|j| j.is_some()).count();
+    let n = height + width - r;
+
+    let mut m = Array2::zeros((n, n));
+    let mut mask = Array2::from_elem((n, n), true);
+
+    for i in 0..height {
+        for j in 0..width {
+            set!(m, i, j, cost(i, j));
+            set!(mask, i, j, allowed(i, j));
+        }
+    }
+
+    for i in height..n {
+        for j in width..n {
+            set!(mask, i, j, false);
+        }
+    }
+
+    let stars = solve(&mut m, Some(&mask))
+        .expect("padded problem always has a perfect matching");
+
+    if stable {
+        lexicographic(&m, Some(&mask), &stars, height)
+            .into_iter()
+            .take(height)
+            .map(|j| if j < width { Some(j) } else { None })
+            .collect()
+    } else {
+        (0..height)
+            .map(|i| (0..width).find(|&j| get!(stars, i, j)))
+            .collect()
+    }
+}
+
+/// Error returned by [`AssignmentProblem::solve`](struct.AssignmentProblem.html#method.solve).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The matrix doesn't match its stated dimensions.
+    Dimension(DimensionError),
+    /// The objective couldn't be represented in the cost type.
+    Overflow(OverflowError),
+    /// The locked entries contradict each other or the other constraints.
+    Infeasible(InfeasibleError),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+        SolveError::Dimension(ref error) => error.fmt(f),
+        SolveError::Overflow(ref error) => error.fmt(f),
+        SolveError::Infeasible(ref error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+        SolveError::Dimension(ref error) => Some(error),
+        SolveError::Overflow(ref error) => Some(error),
+        SolveError::Infeasible(ref error) => Some(error),
+        }
+    }
+}
+
+impl From<DimensionError> for SolveError {
+    fn from(error: DimensionError) -> Self { SolveError::Dimension(error) }
+}
+
+impl From<OverflowError> for SolveError {
+    fn from(error: OverflowError) -> Self { SolveError::Overflow(error) }
+}
+
+impl From<InfeasibleError> for SolveError {
+    fn from(error: InfeasibleError) -> Self { SolveError::Infeasible(error) }
+}
+
+/// Solution returned by [`AssignmentProblem::solve`](struct.AssignmentProblem.html#method.solve).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assignment<N> {
+    rows: Vec<Option<usize>>,
+    cost: N,
+}
+
+impl<N: Copy> Assignment<N> {
+
+    /// Returns a slice where entry `i` is `Some(j)` if row `i`
+    /// is assigned to column `j`, and `None` if it's unassigned.
+    pub fn rows(&self) -> &[Option<usize>] {
+        &self.rows
+    }
+
+    /// Returns the total of the original entries chosen by the assignment,
+    /// i.e. the total cost when minimizing, or the total profit when maximizing.
+    pub fn cost(&self) -> N {
+        self.cost
+    }
+
+    /// Consumes the assignment, returning the same vector as `rows`.
+    pub fn into_vec(self) -> Vec<Option<usize>> {
+        self.rows
+    }
+}
+
+impl<N> From<Assignment<N>> for Vec<Option<usize>> {
+    fn from(assignment: Assignment<N>) -> Self {
+        assignment.rows
+    }
+}
+
+impl<N> IntoIterator for Assignment<N> {
+    type Item = Option<usize>;
+    type IntoIter = std::vec::IntoIter<Option<usize>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.into_iter()
+    }
+}
+
+impl<'a, N> IntoIterator for &'a Assignment<N> {
+    type Item = &'a Option<usize>;
+    type IntoIter = std::slice::Iter<'a, Option<usize>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.iter()
+    }
+}
+
+/// Permutation of `0..n`, as returned by [`minimize_permutation`](fn.minimize_permutation.html)
+/// for square matrices, where every row is matched.
+///
+/// Maps each row `i` to the column `apply(i)` it's assigned to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    map: Vec<usize>,
+}
+
+impl Permutation {
+
+    /// Returns the identity permutation of `0..n`.
+    pub fn identity(n: usize) -> Self {
+        Permutation { map: (0..n).collect() }
+    }
+
+    /// Converts an assignment into a permutation, or returns `None`
+    /// unless every row is assigned to a distinct column in `0..n`,
+    /// where `n` is the number of rows.
+    pub fn from_assignment(assignment: &[Option<usize>]) -> Option<Self> {
+        let n = assignment.len();
+        let mut used = FixedBitSet::with_capacity(n);
+        let mut map = Vec::with_capacity(n);
+        for &j in assignment {
+            match j {
+                Some(j) if j < n && off!(used, j) => {
+                    used.insert(j);
+                    map.push(j);
+                }
+                _ => return None,
+            }
+        }
+        Some(Permutation { map })
+    }
+
+    /// Returns the number of elements permuted.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether this is the permutation of no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the image of `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn apply(&self, i: usize) -> usize {
+        self.map[i]
+    }
+
+    /// Returns the inverse permutation, which maps each column back to its row.
+    pub fn inverse(&self) -> Self {
+        let mut map = vec![0; self.map.len()];
+        for (i, &j) in self.map.iter().enumerate() {
+            map[j] = i;
+        }
+        Permutation { map }
+    }
+
+    /// Returns the composition that applies `other` first, then `self`,
+    /// i.e. `p.compose(&q).apply(i) == p.apply(q.apply(i))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two permutations have different lengths.
+    pub fn compose(&self, other: &Permutation) -> Self {
+        assert_eq!(self.len(), other.len(), "cannot compose permutations of different lengths");
+        Permutation { map: other.map.iter().map(|&j| self.map[j]).collect() }
+    }
+
+    /// Returns a slice where entry `i` is the image of `i`.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.map
+    }
+
+    /// Returns the equivalent assignment, where every row is `Some`.
+    pub fn to_assignment(&self) -> Vec<Option<usize>> {
+        self.map.iter().map(|&j| Some(j)).collect()
+    }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for an `n * n` matrix, returning the
+/// optimal assignment as a [`Permutation`](struct.Permutation.html) of the columns.
+///
+/// # Errors
+///
+/// Returns a `DimensionError` if `matrix` doesn't have exactly `n * n` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_permutation;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 5, 6,
+///         7, 8, 9,
+///     ];
+///
+///     let p = minimize_permutation(&matrix, 3).unwrap();
+///     assert_eq!(p.as_slice(), &[2, 1, 0]);
+///     assert_eq!(p.compose(&p.inverse()).as_slice(), &[0, 1, 2]);
+/// }
+/// ```
+pub fn minimize_permutation<N>(matrix: &[N], n: usize) -> Result<Permutation, DimensionError>
+    where N: NumAssign + PrimInt
+{
+    check_dimensions(matrix.len(), n, n)?;
+    let assignment = minimize(matrix, n, n);
+    Ok(Permutation::from_assignment(&assignment).expect("square matrices are fully matched"))
+}
+
+/// Builder for assignment problems with an objective and constraints.
+///
+/// Combines the options offered by the various `minimize_*` functions
+/// into a single entry point:
+///
+/// - `maximize`: maximize the total instead of minimizing it.
+/// - `gate`: forbid entries worse than a threshold.
+/// - `forbid`: forbid individual entries.
+/// - `lock`: require individual entries.
+/// - `stable_ties`: break ties deterministically.
+///
+/// Rows that can't be matched because of forbidden entries are left unassigned.
+/// Otherwise, the solution matches as many rows as possible, and then optimizes
+/// the total among such matchings. Without any constraints, this matches
+/// `min(height, width)` rows, just like `minimize`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::AssignmentProblem;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 9,
+///         2, 1, 9,
+///         9, 9, 9,
+///     ];
+///
+///     let assignment = AssignmentProblem::new(&matrix, 3, 3)
+///         .gate(5)
+///         .forbid(0, 0)
+///         .solve()
+///         .unwrap();
+///
+///     assert_eq!(assignment.rows(), &[Some(1), Some(0), None]);
+///     assert_eq!(assignment.cost(), 4);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AssignmentProblem<'a, N: 'a> {
+    matrix: &'a [N],
+    height: usize,
+    width: usize,
+    maximize: bool,
+    gate: Option<N>,
+    forbidden: Vec<(usize, usize)>,
+    locked: Vec<(usize, usize)>,
+    stable: bool,
+}
+
+impl<'a, N: NumAssign + PrimInt> AssignmentProblem<'a, N> {
+
+    /// Creates a problem minimizing the total cost of `matrix`,
+    /// a 1D slice in row-major order with the given dimensions.
+    pub fn new(matrix: &'a [N], height: usize, width: usize) -> Self {
+        AssignmentProblem {
+            matrix,
+            height,
+            width,
+            maximize: false,
+            gate: None,
+            forbidden: Vec::new(),
+            locked: Vec::new(),
+            stable: false,
+        }
+    }
+
+    /// Maximizes the total instead of minimizing it, as in [`maximize`](fn.maximize.html).
+    #[must_use]
+    pub fn maximize(mut self) -> Self {
+        self.maximize = true;
+        self
+    }
+
+    /// Forbids every entry worse than `threshold`: entries greater than `threshold`
+    /// when minimizing, or less than `threshold` when maximizing.
+    ///
+    /// Rows are left unassigned rather than matched through such entries.
+    #[must_use]
+    pub fn gate(mut self, threshold: N) -> Self {
+        self.gate = Some(threshold);
+        self
+    }
+
+    /// Forbids matching row `i` to column `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(i, j)` is out of bounds.
+    #[must_use]
+    pub fn forbid(mut self, i: usize, j: usize) -> Self {
+        assert!(i < self.height && j < self.width, "entry ({}, {}) out of bounds", i, j);
+        self.forbidden.push((i, j));
+        self
+    }
+
+    /// Requires matching row `i` to column `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(i, j)` is out of bounds.
+    #[must_use]
+    pub fn lock(mut self, i: usize, j: usize) -> Self {
+        assert!(i < self.height && j < self.width, "entry ({}, {}) out of bounds", i, j);
+        self.locked.push((i, j));
+        self
+    }
+
+    /// Breaks ties between optimal solutions deterministically, independent of
+    /// the internal iteration order: each row in turn is assigned to the lowest
+    /// column possible, given the choices for the rows before it.
+    ///
+    /// This takes an extra `O(n^4)` time in the worst case, on heavily tied inputs.
+    #[must_use]
+    pub fn stable_ties(mut self) -> Self {
+        self.stable = true;
+        self
+    }
+
+    /// Solves the problem.
+    ///
+    /// # Errors
+    ///
+    /// - `SolveError::Dimension` if the matrix doesn't match its dimensions.
+    /// - `SolveError::Overflow` if maximizing overflows, as in [`try_maximize`](fn.try_maximize.html).
+    /// - `SolveError::Infeasible` if two locked entries share a row or column,
+    ///   or a locked entry is forbidden.
+    pub fn solve(&self) -> Result<Assignment<N>, SolveError> {
+
+        let (height, width, matrix) = (self.height, self.width, self.matrix);
+        check_dimensions(matrix.len(), height, width)?;
+
+        let mut allowed = Array2::from_elem((height, width), true);
+
+        if let Some(threshold) = self.gate {
+            for i in 0..height {
+                for j in 0..width {
+                    let value = matrix[width * i + j];
+                    let worse = if self.maximize { value < threshold } else { value > threshold };
+                    if worse { set!(allowed, i, j, false) }
+                }
+            }
+        }
+
+        for &(i, j) in &self.forbidden {
+            set!(allowed, i, j, false);
+        }
+
+        // Locked entries must be allowed, and no two may share a row or column
+        let mut row_lock = vec![None; height];
+        let mut col_lock = vec![None; width];
+        for &(i, j) in &self.locked {
+            if !get!(allowed, i, j)
+            || row_lock[i].unwrap_or(j) != j
+            || col_lock[j].unwrap_or(i) != i {
+                return Err(InfeasibleError.into())
+            }
+            row_lock[i] = Some(j);
+            col_lock[j] = Some(i);
+        }
+
+        // Locking (i, j) forbids every other entry in row i and column j
+        for &(i, j) in &self.locked {
+            for k in (0..width).filter(|&k| k != j) { set!(allowed, i, k, false) }
+            for k in (0..height).filter(|&k| k != i) { set!(allowed, k, j, false) }
+        }
+
+        // Transform the objective into non-negative costs to minimize. As in
+        // `maximize_floored`, entries that can't be matched are never read, so
+        // they get cost zero and don't take part in the range of the objective.
+        let costs = if self.maximize {
+            let max = matrix.iter()
+                .enumerate()
+                .filter(|&(k, _)| get!(allowed, k / width, k % width))
+                .map(|(_, &v)| v)
+                .max()
+                .unwrap_or_else(N::zero);
+            matrix.iter()
+                .enumerate()
+                .map(|(k, &v)| {
+                    if !get!(allowed, k / width, k % width) { return Ok(N::zero()) }
+                    max.checked_sub(&v)
+                        .ok_or(OverflowError { row: k / width, col: k % width })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            matrix.iter().map(clamp).collect()
+        };
+
+        let rows = solve_partial(
+            height,
+            width,
+            |i, j| costs[width * i + j],
+            |i, j| get!(allowed, i, j),
+            self.stable,
+        );
+
+        let cost = rows.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| matrix[width * i + j]))
+            .fold(N::zero(), |acc, v| acc + v);
+
+        Ok(Assignment { rows, cost })
+    }
+}
+
+/// Creates a problem minimizing the total cost of a 2D array, with its dimensions.
+///
+/// # Panics
+///
+/// Panics if `matrix` isn't laid out contiguously in row-major order, such as a
+/// transposed view or a slice with a step. Use `as_standard_layout` to copy
+/// such an array into one that is.
+impl<'a, N: NumAssign + PrimInt> From<&'a Array2<N>> for AssignmentProblem<'a, N> {
+    fn from(matrix: &'a Array2<N>) -> Self {
+        let (height, width) = matrix.dim();
+        let slice = matrix.as_slice().expect("matrix must be in standard layout");
+        AssignmentProblem::new(slice, height, width)
+    }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for floating point costs, which
+/// reuses the exact integer algorithm at a chosen resolution.
+///
+/// Each cost is multiplied by `scale` and rounded to the nearest `i64`, and
+/// the resulting integer matrix is minimized. The result is optimal for the
+/// rounded costs, which is the trade-off `scale` controls:
+///
+/// - Too small a `scale` rounds distinct costs together, and can lose the true optimum.
+///   Costs that differ by more than `1 / scale` are always distinguished, and the result
+///   is within `min(height, width) / scale` of the true optimal cost.
+/// - Too large a `scale` can overflow. Every scaled cost must fit in an `i64`, and as with
+///   any integer matrix, the dual updates may accumulate up to `min(height, width)` times
+///   the largest scaled cost.
+///
+/// As in `minimize`, negative costs are clamped to zero.
+///
+/// # Panics
+///
+/// Panics if any scaled cost is not finite, or doesn't fit in an `i64`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_scaled_f64;
+///
+/// fn main() {
+///     let matrix = vec![
+///         0.10, 0.20,
+///         0.25, 0.50,
+///     ];
+///
+///     assert_eq!(minimize_scaled_f64(&matrix, 2, 2, 100.0), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_scaled_f64(matrix: &[f64], height: usize, width: usize, scale: f64) -> Vec<Option<usize>> {
+    let limit = i64::MAX as f64;
+    let costs = matrix[..height * width].iter()
+        .map(|&cost| {
+            let scaled = (cost * scale).round();
+            assert!(scaled.is_finite() && scaled.abs() < limit, "scaled cost {} out of range", scaled);
+            scaled as i64
+        })
+        .collect::<Vec<_>>();
+
+    minimize(&costs, height, width)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) where columns belong to groups, and at
+/// most one column from each group may be used across the whole assignment.
+///
+/// Column `j` belongs to group `groups[j]`. Since a group is used at most once, the
+/// row assigned to it might as well take its cheapest column in that group. So this
+/// collapses each group into a single column holding the per-row minimum, solves the
+/// resulting `height * g` problem for `g` distinct groups, and maps each group back
+/// to the column it was collapsed from. Ties within a group go to the lowest column.
+///
+/// Group ids don't need to be contiguous. At most `min(height, g)` rows are assigned.
+///
+/// # Panics
+///
+/// Panics if `groups` does not have exactly `width` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_column_groups;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 5,
+///         2, 1, 5,
+///     ];
+///
+///     // Columns 0 and 1 are mutually exclusive
+///     assert_eq!(
+///         minimize_with_column_groups(&matrix, 2, 3, &[7, 7, 3]),
+///         vec![Some(0), Some(2)]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_column_groups<N>(matrix: &[N], height: usize, width: usize, groups: &[usize]) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(groups.len(), width, "groups must have one entry per column");
+
+    // Distinct groups, in increasing order
+    let mut ids = groups.to_vec();
+    ids.sort();
+    ids.dedup();
+    let g = ids.len();
+
+    // Cheapest column of each group, for each row
+    let mut best = vec![None::<usize>; height * g];
+    for i in 0..height {
+        for j in 0..width {
+            let k = g * i + ids.binary_search(&groups[j]).unwrap();
+            let cost = clamp(&matrix[width * i + j]);
+            match best[k] {
+                Some(b) if clamp(&matrix[width * i + b]) <= cost => (),
+                _ => best[k] = Some(j),
+            }
+        }
+    }
+
+    let costs = best.iter()
+        .enumerate()
+        .map(|(k, &j)| clamp(&matrix[width * (k / g) + j.unwrap()]))
+        .collect::<Vec<_>>();
+
+    minimize(&costs, height, g)
+        .into_iter()
+        .enumerate()
+        .map(|(i, group)| group.and_then(|group| best[g * i + group]))
+        .collect()
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also returns the starred zeros
+/// at the solution, as `(row, column)` positions in the original orientation.
+///
+/// The starred zeros are the raw output of the algorithm, before it's packaged
+/// into one `Option` per row. They're listed in row-major order, and there are
+/// exactly `min(height, width)` of them.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_stars;
+///
+/// fn main() {
+///     let matrix = vec![
+///         5, 5,
+///         1, 0,
+///         2, 3,
+///     ];
+///
+///     let (assignment, stars) = minimize_with_stars(&matrix, 3, 2);
+///
+///     assert_eq!(assignment, vec![None, Some(1), Some(0)]);
+///     assert_eq!(stars, vec![(1, 1), (2, 0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_stars<N>(matrix: &[N], height: usize, width: usize) -> (Vec<Option<usize>>, Vec<(usize, usize)>)
+    where N: NumAssign + PrimInt
+{
+    // No possible assignment
+    if height == 0 || width == 0 { return (vec![None; height], Vec::new()) }
+
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    let stars = solve(&mut m, None).unwrap();
+    let positions = unorient(&stars, height, width)
+        .indexed_iter()
+        .filter(|&(_, &star)| star)
+        .map(|(position, _)| position)
+        .collect();
+
+    (assignment(&stars, width < height), positions)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that takes ownership of `matrix`,
+/// and reuses its allocation as the workspace when possible.
+///
+/// `matrix` is clamped in place and reshaped directly into the workspace, avoiding
+/// the `O(height * width)` copy `minimize` makes.
+///
+/// # Panics
+///
+/// Panics if `matrix` has fewer than `height * width` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_owned;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 5, 6,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_owned(matrix, 3, 3), vec![Some(2), Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_owned<N>(mut matrix: Vec<N>, height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    if height <= 1 || width <= 1 {
+        return minimize(&matrix, height, width)
+    }
+
+    matrix.truncate(height * width);
+    for cost in &mut matrix {
+        *cost = clamp(cost);
+    }
+
+    let m = Array2::from_shape_vec((height, width), matrix)
+        .expect("matrix has fewer than height * width entries");
+
+    // View the matrix transposed if width < height. Unlike `orient`, this only swaps
+    // the strides, since copying would defeat the point of reusing the caller's buffer.
+    let transposed = width < height;
+    let mut m = if transposed { m.reversed_axes() } else { m };
+
+    let stars = solve(&mut m, None).unwrap();
+    assignment(&stars, transposed)
+}
+
+/// Finds a maximum-weight matching in the complete bipartite graph with `left`
+/// vertices on one side and `right` vertices on the other, where the edge
+/// between `u` and `v` has weight `weight(u, v)`.
+///
+/// Returns a vector of length `left`, where entry `u` is the right vertex matched
+/// to left vertex `u`. Since the graph is complete, `min(left, right)` edges are
+/// always matched. This evaluates `weight` once per edge to build a cost matrix
+/// for [`maximize`](fn.maximize.html), and panics under the same conditions;
+/// see [`try_max_weight_matching`](fn.try_max_weight_matching.html).
+///
+/// Missing edges must be modeled explicitly: use the `forbid` method of
+/// [`AssignmentProblem`](struct.AssignmentProblem.html) rather than a sentinel weight.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::max_weight_matching;
+///
+/// fn main() {
+///     // Weight is highest when u and v are close together
+///     let weight = |u: usize, v: usize| 10 - (u as i32 - v as i32).abs();
+///
+///     assert_eq!(max_weight_matching(3, 4, weight), vec![Some(0), Some(1), Some(2)]);
+/// }
+/// ```
+#[must_use]
+pub fn max_weight_matching<N, F>(left: usize, right: usize, weight: F) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt,
+          F: Fn(usize, usize) -> N,
+{
+    try_max_weight_matching(left, right, weight)
+        .expect("max_weight_matching: weight range exceeds the cost type")
+}
+
+/// Fallible variant of [`max_weight_matching`](fn.max_weight_matching.html).
+///
+/// # Errors
+///
+/// Returns an `OverflowError` identifying the edge `(u, v)` whose weight
+/// can't be transformed, as in [`try_maximize`](fn.try_maximize.html).
+pub fn try_max_weight_matching<N, F>(left: usize, right: usize, weight: F) -> Result<Vec<Option<usize>>, OverflowError>
+    where N: NumAssign + PrimInt,
+          F: Fn(usize, usize) -> N,
+{
+    let matrix = (0..left)
+        .flat_map(|u| (0..right).map(move |v| (u, v)))
+        .map(|(u, v)| weight(u, v))
+        .collect::<Vec<_>>();
+
+    try_maximize(&matrix, left, right)
+}
+
+/// Reusable solver that keeps its workspaces between calls, so that solving many
+/// problems in a row only allocates when a problem is larger than any before it.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::Solver;
+///
+/// fn main() {
+///     let mut solver = Solver::with_capacity(3, 3);
+///
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 5, 6,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(solver.minimize(&matrix, 3, 3), vec![Some(2), Some(1), Some(0)]);
+///     assert_eq!(solver.minimize(&matrix[..6], 2, 3), vec![Some(2), Some(0)]);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Solver<N> {
+    costs: Vec<N>,
+    stars: Vec<bool>,
+    primes: Vec<bool>,
+    row_cover: FixedBitSet,
+    col_cover: FixedBitSet,
+    columns: Vec<usize>,
+    incremental: Option<Incremental<N>>,
+}
+
+impl<N: NumAssign + PrimInt> Default for Solver<N> {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+impl<N: NumAssign + PrimInt> Solver<N> {
+
+    /// Creates a solver without allocating any workspace.
+    pub fn new() -> Self {
+        Solver {
+            costs: Vec::new(),
+            stars: Vec::new(),
+            primes: Vec::new(),
+            row_cover: FixedBitSet::with_capacity(0),
+            col_cover: FixedBitSet::with_capacity(0),
+            columns: Vec::new(),
+            incremental: None,
+        }
+    }
+
+    /// Creates a solver with workspaces large enough for a `height * width` problem.
+    pub fn with_capacity(height: usize, width: usize) -> Self {
+        let mut solver = Solver::new();
+        if height > 1 && width > 1 {
+            let (h, w) = if width < height { (width, height) } else { (height, width) };
+            solver.costs.reserve_exact(h * w);
+            solver.stars.reserve_exact(h * w);
+            solver.primes.reserve_exact(h * w);
+            solver.row_cover.grow(h);
+            solver.col_cover.grow(w);
+            solver.columns.reserve_exact(w);
+        }
+        solver
+    }
+
+    /// Returns the number of bytes allocated to solve a `height * width` problem
+    /// with a fresh solver, including the returned assignment vector.
+    ///
+    /// This covers the cost workspace, the starred and primed zero masks, the row
+    /// and column covers, the column indices visited by Step 6, and the output.
+    /// Problems with a single row or column skip the workspaces entirely, and only
+    /// need the output.
+    pub fn required_bytes(height: usize, width: usize) -> usize {
+        if height == 0 || width == 0 { return 0 }
+
+        let output = height * mem::size_of::<Option<usize>>();
+        if height == 1 || width == 1 { return output }
+
+        let (h, w) = if width < height { (width, height) } else { (height, width) };
+        let blocks = |bits: usize| bits.div_ceil(32) * mem::size_of::<u32>();
+
+        h * w * (mem::size_of::<N>() + 2 * mem::size_of::<bool>())
+            + blocks(h)
+            + blocks(w)
+            + w * mem::size_of::<usize>()
+            + output
+    }
+
+    /// Returns the number of bytes currently held by this solver's workspaces,
+    /// including the state kept by [`minimize_incremental`](#method.minimize_incremental).
+    pub fn allocated_bytes(&self) -> usize {
+        self.costs.capacity() * mem::size_of::<N>()
+            + (self.stars.capacity() + self.primes.capacity()) * mem::size_of::<bool>()
+            + (self.row_cover.as_slice().len() + self.col_cover.as_slice().len()) * mem::size_of::<u32>()
+            + self.columns.capacity() * mem::size_of::<usize>()
+            + self.incremental.as_ref().map_or(0, Incremental::allocated_bytes)
+    }
+
+    /// Equivalent to [`minimize`](fn.minimize.html), but reuses this solver's workspaces.
+    #[must_use]
+    pub fn minimize(&mut self, matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>> {
+        self.minimize_from(matrix, height, width, None)
+    }
+
+    /// Variant of [`minimize`](#method.minimize) that takes the minimum of each row
+    /// of `matrix` in `row_mins`, instead of finding it again in Step 1.
+    ///
+    /// Useful in tight loops over related matrices whose row minima are already
+    /// known. The result is identical to `minimize`'s. Subtracting a constant from
+    /// a row only preserves the optimum when every row is assigned, so a matrix with
+    /// more rows than columns, which is solved transposed, ignores `row_mins`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row_mins` doesn't have exactly `height` entries. In debug builds,
+    /// also panics if an entry of `row_mins` isn't the minimum of its row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate hungarian;
+    ///
+    /// use hungarian::Solver;
+    ///
+    /// fn main() {
+    ///     let mut solver = Solver::new();
+    ///
+    ///     let matrix = vec![
+    ///         1, 2, 1,
+    ///         4, 5, 6,
+    ///         7, 8, 9,
+    ///     ];
+    ///
+    ///     let assignment = solver.minimize_prereduced(&matrix, 3, 3, &[1, 4, 7]);
+    ///     assert_eq!(assignment, vec![Some(2), Some(1), Some(0)]);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn minimize_prereduced(&mut self, matrix: &[N], height: usize, width: usize, row_mins: &[N]) -> Vec<Option<usize>> {
+        assert!(row_mins.len() == height, "minimize_prereduced: expected {} row minima, got {}", height, row_mins.len());
+        debug_assert!(
+            (0..height).all(|i| matrix[width * i..width * (i + 1)].iter().min() == Some(&row_mins[i])),
+            "minimize_prereduced: row_mins must hold the minimum of each row",
+        );
+        self.minimize_from(matrix, height, width, Some(row_mins))
+    }
+
+    /// Shared body of `minimize` and `minimize_prereduced`, which skips Step 1 when
+    /// given row minima for a matrix that isn't transposed.
+    fn minimize_from(&mut self, matrix: &[N], height: usize, width: usize, row_mins: Option<&[N]>) -> Vec<Option<usize>> {
+
+        if height <= 1 || width <= 1 { return minimize(matrix, height, width) }
+
+        let transposed = width < height;
+        let (h, w) = if transposed { (width, height) } else { (height, width) };
+        let row_mins = row_mins.filter(|_| !transposed);
+
+        let mut costs = mem::take(&mut self.costs);
+        refill(&mut costs, h * w, N::zero());
+        for i in 0..height {
+            let min = row_mins.map_or(N::zero(), |mins| clamp(&mins[i]));
+            for j in 0..width {
+                let k = if transposed { j * w + i } else { i * w + j };
+                costs[k] = clamp(&matrix[width * i + j]) - min;
+            }
+        }
+
+        let mut stars = mem::take(&mut self.stars);
+        let mut primes = mem::take(&mut self.primes);
+        refill(&mut stars, h * w, false);
+        refill(&mut primes, h * w, false);
+        self.row_cover.grow(h);
+        self.col_cover.grow(w);
+        self.columns.clear();
+        self.columns.reserve_exact(w);
+
+        let mut m = Array2::from_shape_vec((h, w), costs).unwrap();
+        let mut stars = Array2::from_shape_vec((h, w), stars).unwrap();
+        let mut primes = Array2::from_shape_vec((h, w), primes).unwrap();
+
+        // Without forbidden entries, every row can always be matched
+        let solve = if row_mins.is_some() { solve_reduced_in } else { solve_in };
+        solve(
+            &mut m,
+            None,
+            &mut stars,
+            &mut primes,
+            &mut self.row_cover,
+            &mut self.col_cover,
+            &mut self.columns,
+            &mut unobserved,
+        );
+        let result = assignment(&stars, transposed);
+
+        self.costs = m.into_raw_vec();
+        self.stars = stars.into_raw_vec();
+        self.primes = primes.into_raw_vec();
+        result
+    }
+
+    /// Variant of [`minimize`](#method.minimize) that keeps the optimal solution
+    /// and its reduced costs, so that [`update_cost`](#method.update_cost) can
+    /// repair it after a single entry changes.
+    ///
+    /// The problem is padded into a square one with zero-cost dummy rows or columns,
+    /// and solved by augmenting along shortest paths, one row at a time. This keeps
+    /// `O(n^2)` state for `n = max(height, width)`, in addition to a copy of the
+    /// clamped costs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate hungarian;
+    ///
+    /// use hungarian::Solver;
+    ///
+    /// fn main() {
+    ///     let mut solver = Solver::new();
+    ///
+    ///     let matrix = vec![
+    ///         1, 2, 1,
+    ///         4, 3, 6,
+    ///         7, 8, 9,
+    ///     ];
+    ///
+    ///     assert_eq!(solver.minimize_incremental(&matrix, 3, 3), &[Some(2), Some(1), Some(0)]);
+    ///     assert_eq!(solver.update_cost(1, 1, 20), &[Some(2), Some(0), Some(1)]);
+    ///     assert_eq!(solver.update_cost(1, 1, 3), &[Some(2), Some(1), Some(0)]);
+    /// }
+    /// ```
+    pub fn minimize_incremental(&mut self, matrix: &[N], height: usize, width: usize) -> &[Option<usize>] {
+        let mut incremental = Incremental::new(matrix, height, width);
+        for i in 0..height.max(width) {
+            incremental.augment(i);
+        }
+        incremental.refresh();
+        &self.incremental.insert(incremental).result
+    }
+
+    /// Changes entry `(i, j)` of the problem last passed to
+    /// [`minimize_incremental`](#method.minimize_incremental) to `cost`, and
+    /// returns the new optimal assignment.
+    ///
+    /// Rather than solving from scratch, this adjusts the reduced costs of row `i`
+    /// to keep them non-negative, which can only break the match of row `i`. If it
+    /// does, a single augmenting path search rematches it in `O(n^2)` time. As in
+    /// `minimize`, costs are clamped to be greater or equal to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `minimize_incremental` hasn't been called, or if `(i, j)` is out of bounds.
+    pub fn update_cost(&mut self, i: usize, j: usize, cost: N) -> &[Option<usize>] {
+        let incremental = self.incremental.as_mut()
+            .expect("update_cost: call minimize_incremental first");
+        incremental.update(i, j, clamp(&cost));
+        incremental.refresh();
+        &incremental.result
+    }
+}
+
+/// Internal state for [`Solver::update_cost`](struct.Solver.html#method.update_cost).
+///
+/// Holds an optimal perfect matching of the `n * n` padded problem, and the reduced
+/// costs `c[i][j] - u[i] - v[j]` for some dual potentials `u` and `v`. Reduced costs
+/// are kept instead of the potentials so that unsigned cost types work: every one
+/// is non-negative, and zero on every matched entry, which certifies optimality.
+#[derive(Clone, Debug)]
+struct Incremental<N> {
+    height: usize,
+    width: usize,
+    n: usize,
+    costs: Vec<N>,
+    reduced: Vec<N>,
+    col_of: Vec<Option<usize>>,
+    row_of: Vec<Option<usize>>,
+    result: Vec<Option<usize>>,
+}
+
+impl<N: NumAssign + PrimInt> Incremental<N> {
+
+    /// Pads `matrix` into an unmatched square problem, with zero potentials.
+    fn new(matrix: &[N], height: usize, width: usize) -> Self {
+        let n = height.max(width);
+        let costs = matrix[..height * width].iter().map(clamp).collect::<Vec<_>>();
+        let mut reduced = vec![N::zero(); n * n];
+        for i in 0..height {
+            reduced[n * i..n * i + width].copy_from_slice(&costs[width * i..width * (i + 1)]);
+        }
+        Incremental {
+            height,
+            width,
+            n,
+            costs,
+            reduced,
+            col_of: vec![None; n],
+            row_of: vec![None; n],
+            result: Vec::with_capacity(height),
+        }
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        (self.costs.capacity() + self.reduced.capacity()) * mem::size_of::<N>()
+            + (self.col_of.capacity() + self.row_of.capacity() + self.result.capacity())
+                * mem::size_of::<Option<usize>>()
+    }
+
+    /// Matches the unmatched row `start` along a shortest augmenting path,
+    /// using Dijkstra's algorithm over the reduced costs.
+    fn augment(&mut self, start: usize) {
+        let n = self.n;
+        let mut dist = vec![None; n];
+        let mut parent = vec![0; n];
+        let mut done = FixedBitSet::with_capacity(n);
+        let mut tree = vec![(start, N::zero())];
+
+        let (mut i, mut d) = (start, N::zero());
+        let (end, total) = loop {
+            for j in (0..n).filter(|&j| off!(done, j)) {
+                let next = d + self.reduced[n * i + j];
+                let better = match dist[j] {
+                    Some(old) => next < old,
+                    None => true,
+                };
+                if better {
+                    dist[j] = Some(next);
+                    parent[j] = i;
+                }
+            }
+
+            let j = (0..n)
+                .filter(|&j| off!(done, j))
+                .min_by_key(|&j| dist[j])
+                .unwrap();
+
+            done.insert(j);
+            d = dist[j].unwrap();
+            match self.row_of[j] {
+                Some(owner) => { i = owner; tree.push((i, d)) }
+                None => break (j, d),
+            }
+        };
+
+        // Shift the potentials so the tree stays tight, and every reduced cost
+        // stays non-negative: row `i` at distance `d` gains `total - d`, and so
+        // does column `j` at distance `d`.
+        for j in done.ones() {
+            let gain = total - dist[j].unwrap();
+            for i in 0..n { self.reduced[n * i + j] += gain }
+        }
+        for &(i, d) in &tree {
+            let gain = total - d;
+            for j in 0..n { self.reduced[n * i + j] -= gain }
+        }
+
+        // Flip the path from the free column `end` back to `start`
+        let mut j = end;
+        loop {
+            let i = parent[j];
+            let prev = self.col_of[i];
+            self.col_of[i] = Some(j);
+            self.row_of[j] = Some(i);
+            match prev {
+                Some(prev) if i != start => j = prev,
+                _ => break,
+            }
+        }
+    }
+
+    /// Changes the (clamped) cost of entry `(i, j)`, and repairs the matching.
+    fn update(&mut self, i: usize, j: usize, cost: N) {
+        assert!(i < self.height && j < self.width, "update_cost: ({}, {}) out of bounds", i, j);
+
+        let n = self.n;
+        let old = mem::replace(&mut self.costs[self.width * i + j], cost);
+        let reduced = self.reduced[n * i + j];
+        let matched = self.col_of[i] == Some(j);
+
+        if cost >= old {
+            self.reduced[n * i + j] = reduced + (cost - old);
+            if !matched || cost == old { return }
+        } else if old - cost <= reduced {
+            self.reduced[n * i + j] = reduced - (old - cost);
+            return
+        } else {
+            // Lower row `i`'s potential so that `(i, j)` becomes tight
+            let lift = old - cost - reduced;
+            for k in 0..n { self.reduced[n * i + k] += lift }
+            self.reduced[n * i + j] = N::zero();
+            if matched { return }
+        }
+
+        // Row `i`'s match is no longer tight, so it needs to be rematched
+        let k = self.col_of[i].take().unwrap();
+        self.row_of[k] = None;
+        self.augment(i);
+    }
+
+    fn refresh(&mut self) {
+        let width = self.width;
+        self.result.clear();
+        self.result.extend(self.col_of[..self.height].iter().map(|&j| j.filter(|&j| j < width)));
+    }
+}
+
+/// Resizes `buffer` to `len` copies of `value`, growing its allocation to exactly `len` if needed.
+fn refill<T: Clone>(buffer: &mut Vec<T>, len: usize, value: T) {
+    buffer.clear();
+    buffer.reserve_exact(len);
+    buffer.resize(len, value);
+}
+
+/// Streaming solver that keeps an optimal assignment as rows arrive one at a time.
+///
+/// Each [`push_row`](#method.push_row) extends the problem by a single row over
+/// the same `width` columns, and repairs the assignment with one shortest augmenting
+/// path instead of solving from scratch, in `O(height * width)` time per row when
+/// `height <= width`. Earlier rows may be reassigned if that lowers the total cost.
+/// Once there are more rows than columns, some rows are left unassigned, as in
+/// [`minimize`](fn.minimize.html). Costs are clamped to be greater or equal to zero.
+///
+/// Unlike [`Solver::update_cost`](struct.Solver.html#method.update_cost), each new
+/// row's costs are stored offset by the column potentials, which can grow to about
+/// `height` times the largest cost. Costs should stay well below the cost type's
+/// maximum, e.g. within [`max_safe_cost`](fn.max_safe_cost.html).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::OnlineSolver;
+///
+/// fn main() {
+///     let mut solver = OnlineSolver::new(2);
+///
+///     assert_eq!(solver.push_row(&[1, 2]), &[Some(0)]);
+///
+///     // Row 0 gives up column 0, which row 1 needs more
+///     assert_eq!(solver.push_row(&[1, 9]), &[Some(1), Some(0)]);
+///
+///     // Row 2 is cheaper than row 0 in column 1
+///     assert_eq!(solver.push_row(&[9, 0]), &[None, Some(0), Some(1)]);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OnlineSolver<N> {
+    width: usize,
+    reduced: Vec<Vec<N>>,
+    row_gain: Vec<N>,
+    col_gain: Vec<N>,
+    col_of: Vec<Option<usize>>,
+    row_of: Vec<Option<usize>>,
+    result: Vec<Option<usize>>,
+}
+
+impl<N: NumAssign + PrimInt> OnlineSolver<N> {
+
+    /// Creates a solver with no rows, for rows of `width` costs each.
+    pub fn new(width: usize) -> Self {
+        OnlineSolver {
+            width,
+            reduced: Vec::new(),
+            row_gain: Vec::new(),
+            col_gain: vec![N::zero(); width],
+            col_of: Vec::new(),
+            row_of: vec![None; width],
+            result: Vec::new(),
+        }
+    }
+
+    /// Returns the optimal assignment of the rows pushed so far.
+    pub fn assignment(&self) -> &[Option<usize>] {
+        &self.result
+    }
+
+    /// Adds a row of `width` costs, and returns the optimal assignment of every
+    /// row pushed so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `costs` doesn't have exactly `width` entries.
+    pub fn push_row(&mut self, costs: &[N]) -> &[Option<usize>] {
+        assert!(costs.len() == self.width, "push_row: expected {} costs, got {}", self.width, costs.len());
+
+        // The reduced cost of `(i, j)` is `c[i][j] - u[i] + s[j]`, where row gains
+        // `u` and column gains `s` only ever grow. Past `width` rows, each new row
+        // brings a zero cost dummy column, whose gain keeps its entries non-negative.
+        let start = self.reduced.len();
+        if start >= self.col_gain.len() {
+            let top = self.row_gain.iter().cloned().max().unwrap_or_else(N::zero);
+            for (row, &gain) in self.reduced.iter_mut().zip(&self.row_gain) {
+                row.push(top - gain);
+            }
+            self.col_gain.push(top);
+            self.row_of.push(None);
+        }
+
+        let row = self.col_gain.iter()
+            .enumerate()
+            .map(|(j, &gain)| if j < self.width { clamp(&costs[j]) + gain } else { gain })
+            .collect();
+
+        self.reduced.push(row);
+        self.row_gain.push(N::zero());
+        self.col_of.push(None);
+        self.augment(start);
+
+        let width = self.width;
+        self.result.clear();
+        self.result.extend(self.col_of.iter().map(|&j| j.filter(|&j| j < width)));
+        &self.result
+    }
+
+    /// Matches the unmatched row `start` along a shortest augmenting path,
+    /// using Dijkstra's algorithm over the reduced costs.
+    fn augment(&mut self, start: usize) {
+        let n = self.col_gain.len();
+        let mut dist = vec![None; n];
+        let mut parent = vec![0; n];
+        let mut done = FixedBitSet::with_capacity(n);
+        let mut tree = vec![(start, N::zero())];
+
+        let (mut i, mut d) = (start, N::zero());
+        let (end, total) = loop {
+            for j in (0..n).filter(|&j| off!(done, j)) {
+                let next = d + self.reduced[i][j];
+                let better = match dist[j] {
+                    Some(old) => next < old,
+                    None => true,
+                };
+                if better {
+                    dist[j] = Some(next);
+                    parent[j] = i;
+                }
+            }
+
+            let j = (0..n)
+                .filter(|&j| off!(done, j))
+                .min_by_key(|&j| dist[j])
+                .unwrap();
+
+            done.insert(j);
+            d = dist[j].unwrap();
+            match self.row_of[j] {
+                Some(owner) => { i = owner; tree.push((i, d)) }
+                None => break (j, d),
+            }
+        };
+
+        // Shift the gains so the tree stays tight, as in `Incremental::augment`
+        for j in done.ones() {
+            let gain = total - dist[j].unwrap();
+            self.col_gain[j] += gain;
+            for row in &mut self.reduced { row[j] += gain }
+        }
+        for &(i, d) in &tree {
+            let gain = total - d;
+            self.row_gain[i] += gain;
+            for cost in &mut self.reduced[i] { *cost -= gain }
+        }
+
+        // Flip the path from the free column `end` back to `start`
+        let mut j = end;
+        loop {
+            let i = parent[j];
+            let prev = self.col_of[i];
+            self.col_of[i] = Some(j);
+            self.row_of[j] = Some(i);
+            match prev {
+                Some(prev) if i != start => j = prev,
+                _ => break,
+            }
+        }
+    }
+}
+
+/// Computes the total cost of the assignment [`minimize`](fn.minimize.html) would
+/// return, without building the assignment itself.
+///
+/// Costs are clamped to be greater or equal to zero, as in `minimize`. This reads
+/// the cost straight off the final starred zeros, skipping the assignment vector,
+/// which is useful as a bound in search loops that only need the optimal value.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_cost_only;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 5, 6,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_cost_only(&matrix, 3, 3), 13);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_cost_only<N>(matrix: &[N], height: usize, width: usize) -> N
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 { return N::zero() }
+
+    // Single row or column: the cheapest entry
+    if height == 1 || width == 1 {
+        return matrix[..height * width].iter()
+            .map(clamp)
+            .min()
+            .unwrap()
+    }
+
+    let transposed = width < height;
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    let stars = solve(&mut m, None).unwrap();
+
+    stars.indexed_iter()
+        .filter(|&(_, &star)| star)
+        .map(|((i, j), _)| if transposed { (j, i) } else { (i, j) })
+        .fold(N::zero(), |acc, (i, j)| acc + clamp(&matrix[width * i + j]))
+}
+
+/// Computes a lower bound on the cost [`minimize_cost_only`](fn.minimize_cost_only.html)
+/// would return, in `O(height * width)` time and without solving.
+///
+/// This is a bound, not the optimum: the optimal cost may be larger. Every row of the
+/// shorter side is matched, so each contributes at least its cheapest entry. For a
+/// square matrix, every column is matched too, so the cheapest entry of each column
+/// after subtracting the row minima is added as well. The bound is exact whenever
+/// the zeros left after both subtractions contain a complete matching. As in
+/// `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{lower_bound, minimize_cost_only};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 5, 6,
+///         7, 8, 9,
+///     ];
+///
+///     // Row minima 1 + 4 + 7, plus column minima 0 + 1 + 0 of what's left
+///     assert_eq!(lower_bound(&matrix, 3, 3), 13);
+///     assert_eq!(minimize_cost_only(&matrix, 3, 3), 13);
+///
+///     // Rows 1 and 2 both want column 1, which the bound doesn't see
+///     let matrix = vec![
+///         6, 6, 6,
+///         3, 0, 4,
+///         4, 0, 3,
+///     ];
+///
+///     assert_eq!(lower_bound(&matrix, 3, 3), 6);
+///     assert_eq!(minimize_cost_only(&matrix, 3, 3), 9);
+/// }
+/// ```
+#[must_use]
+pub fn lower_bound<N>(matrix: &[N], height: usize, width: usize) -> N
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 { return N::zero() }
+
+    let m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    let (h, w) = m.dim();
+
+    let minima = m.genrows()
+        .into_iter()
+        .map(|row| *row.iter().min().unwrap())
+        .collect::<Vec<_>>();
+    let bound = minima.iter().fold(N::zero(), |acc, &min| acc + min);
+
+    if h < w { return bound }
+
+    (0..w).fold(bound, |acc, j| {
+        acc + (0..h).map(|i| get!(m, i, j) - minima[i]).min().unwrap()
+    })
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that treats entries equal to
+/// `N::max_value()` as unavailable, rather than as very expensive.
+///
+/// Finds a matching of maximum cardinality that avoids every sentinel entry,
+/// and of minimum total cost among those. Rows that can't be matched, such as
+/// a row consisting entirely of `N::max_value()`, are left as `None` instead of
+/// being forced onto a sentinel column. Sentinel entries never take part in the
+/// algorithm's arithmetic, so they can't overflow it. As in `minimize`, costs are
+/// clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_feasible;
+///
+/// fn main() {
+///     let x = u32::MAX;
+///     let matrix = vec![
+///         1, 2, 1,
+///         x, x, x,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_feasible(&matrix, 3, 3), vec![Some(2), None, Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_feasible<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    minimize_with_sentinel(matrix, height, width, N::max_value())
+}
+
+/// Variant of [`minimize_feasible`](fn.minimize_feasible.html) that treats entries
+/// equal to `sentinel` as unavailable, whatever its value relative to the real costs.
+///
+/// This suits data that marks forbidden entries with a special value such as `-1`
+/// or `0`, which `minimize` would otherwise clamp to the cheapest possible cost.
+/// Sentinel entries are never assigned, and never take part in the row reduction.
+/// Rows that can't be matched, such as a row consisting entirely of `sentinel`,
+/// are left as `None`. As in `minimize`, other costs are clamped to be greater or
+/// equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_sentinel;
+///
+/// fn main() {
+///     let matrix = vec![
+///          1,  2, -1,
+///         -1, -1, -1,
+///          7,  9,  9,
+///     ];
+///
+///     assert_eq!(minimize_with_sentinel(&matrix, 3, 3, -1), vec![Some(1), None, Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_sentinel<N>(matrix: &[N], height: usize, width: usize, sentinel: N) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    solve_partial(
+        height,
+        width,
+        |i, j| clamp(&matrix[width * i + j]),
+        |i, j| matrix[width * i + j] != sentinel,
+        false,
+    )
+}
+
+/// Converts an assignment for a `height * width` problem into the equivalent
+/// assignment for the transposed `width * height` problem.
+///
+/// Entry `j` of the result is `Some(i)` exactly when `assignment[i]` is `Some(j)`,
+/// so the result has `width` entries. Since a matching uses each column at most
+/// once, transposing twice gives back the original assignment.
+///
+/// # Panics
+///
+/// Panics if `assignment` has more than `height` entries, or refers to a column
+/// outside of `0..width`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::transpose_assignment;
+///
+/// fn main() {
+///     let assignment = vec![Some(2), None, Some(0)];
+///     let transposed = transpose_assignment(&assignment, 3, 4);
+///
+///     assert_eq!(transposed, vec![Some(2), None, Some(0), None]);
+///     assert_eq!(transpose_assignment(&transposed, 4, 3), assignment);
+/// }
+/// ```
+pub fn transpose_assignment(assignment: &[Option<usize>], height: usize, width: usize) -> Vec<Option<usize>> {
+    assert!(assignment.len() <= height, "assignment has more than height entries");
+    let mut result = vec![None; width];
+    for (i, j) in assignment.iter().enumerate() {
+        if let Some(j) = *j { result[j] = Some(i) }
+    }
+    result
+}
+
+/// Error returned when a cost matrix contains a negative entry.
+///
+/// Identifies the first negative entry in row-major order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegativeCostError<N> {
+    /// Row of the offending entry
+    pub row: usize,
+    /// Column of the offending entry
+    pub col: usize,
+    /// Value of the offending entry
+    pub value: N,
+}
+
+impl<N: fmt::Display> fmt::Display for NegativeCostError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cost at ({}, {}) is negative: {}", self.row, self.col, self.value)
+    }
+}
+
+impl<N: fmt::Debug + fmt::Display> Error for NegativeCostError<N> {}
+
+/// Strict variant of [`minimize`](fn.minimize.html), which rejects negative
+/// entries instead of silently clamping them to zero.
+///
+/// # Errors
+///
+/// Returns a `NegativeCostError` for the first negative entry in row-major order.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{try_minimize_nonneg, NegativeCostError};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1,  2,
+///         2, -1,
+///     ];
+///
+///     assert_eq!(
+///         try_minimize_nonneg(&matrix, 2, 2),
+///         Err(NegativeCostError { row: 1, col: 1, value: -1 })
+///     );
+/// }
+/// ```
+pub fn try_minimize_nonneg<N>(matrix: &[N], height: usize, width: usize) -> Result<Vec<Option<usize>>, NegativeCostError<N>>
+    where N: NumAssign + PrimInt
+{
+    let negative = matrix[..height * width].iter()
+        .position(|&value| value < N::zero());
+
+    if let Some(k) = negative {
+        return Err(NegativeCostError { row: k / width, col: k % width, value: matrix[k] })
+    }
+
+    Ok(minimize(matrix, height, width))
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for totally ordered cost types that
+/// aren't primitive integers, such as `ordered_float::OrderedFloat<f64>` or
+/// `ordered_float::NotNan<f64>`.
+///
+/// The algorithm only needs to compare, add, and subtract costs, and to test
+/// them for zero, which for floating point wrappers is exact equality to `0.0`.
+/// As in `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// # Requires
+///
+/// - No entry is NaN. `OrderedFloat` sorts NaN above every other value, which
+///   makes the arithmetic meaningless; `NotNan` rules it out by construction.
+/// - `N: Copy`. Every step reads and writes entries of the workspace by value,
+///   many times per entry, which is only cheap for plain values. Types that are
+///   only `Clone`, such as arbitrary precision integers, aren't supported:
+///   scale them into a primitive integer type instead.
+///
+/// Floating point subtraction is exact when both operands are equal, so the
+/// zeros the algorithm relies on are found reliably, but rounding elsewhere can
+/// make the result suboptimal by a few ulps on costs of wildly varying magnitude.
+#[must_use]
+pub fn minimize_ordered<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + Copy + Ord
+{
+    let clamp = |cost: N| if cost < N::zero() { N::zero() } else { cost };
+
+    // No possible assignment
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    // Single row: assign it to the cheapest column
+    if height == 1 {
+        return vec![argmin((0..width).map(|j| clamp(matrix[j])))]
+    }
+
+    // Single column: assign it to the cheapest row
+    if width == 1 {
+        let mut result = vec![None; height];
+        if let Some(i) = argmin((0..height).map(|i| clamp(matrix[i]))) {
+            result[i] = Some(0);
+        }
+        return result
+    }
+
+    let transposed = width < height;
+    let mut m = orient(height, width, |i, j| clamp(matrix[width * i + j]));
+
+    let stars = solve(&mut m, None).unwrap();
+    assignment(&stars, transposed)
+}
+
+/// Edge of the residual graph used by `shortest_augmenting_paths`, with unit capacity.
+struct FlowEdge<N> {
+    to: usize,
+    /// Whether the edge has residual capacity
+    open: bool,
+    /// Cost of the forward edge. Backward edges cost `-cost`.
+    cost: N,
+    backward: bool,
+    /// Index of the paired edge in `to`'s adjacency list
+    rev: usize,
+}
+
+/// Internal min-cost flow solver behind `minimize_flow` and `minimize_csr`.
+///
+/// Finds a matching of maximum cardinality, and of minimum total cost among those,
+/// that only uses the `(row, column, cost)` triples in `entries`. Costs are clamped
+/// to be greater or equal to zero.
+///
+/// After `k` augmentations, the matching has minimum cost among those of size `k`,
+/// and each augmenting path costs at least as much as the one before. So with a
+/// `budget`, stopping before the first path that would take the total over it gives
+/// the largest matching within the budget, and of minimum total cost among those.
+/// Runs in `O(r * e * log(height + width))` time for `e` entries and `r` matched
+/// rows, and `O(height + width + e)` space.
+fn shortest_augmenting_paths<N, E>(height: usize, width: usize, entries: E, budget: Option<N>) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt,
+          E: IntoIterator<Item = (usize, usize, N)>,
+{
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    // Rows are nodes `0..height`, columns are `height..height + width`
+    let source = height + width;
+    let sink = source + 1;
+    let mut graph: Vec<Vec<FlowEdge<N>>> = (0..sink + 1).map(|_| Vec::new()).collect();
+
+    let connect = |graph: &mut Vec<Vec<FlowEdge<N>>>, u: usize, v: usize, cost: N| {
+        let (fwd, bwd) = (graph[u].len(), graph[v].len());
+        graph[u].push(FlowEdge { to: v, open: true, cost, backward: false, rev: bwd });
+        graph[v].push(FlowEdge { to: u, open: false, cost, backward: true, rev: fwd });
+    };
+
+    for i in 0..height {
+        connect(&mut graph, source, i, N::zero());
+    }
+
+    for (i, j, cost) in entries {
+        connect(&mut graph, i, height + j, clamp(&cost));
+    }
+
+    for j in 0..width {
+        connect(&mut graph, height + j, sink, N::zero());
+    }
+
+    // Every cost is non-negative, so zero potentials are feasible to start with.
+    // Reduced costs stay non-negative on every edge reachable from the source,
+    // so they're computed in an order that never goes below zero.
+    let mut potential = vec![N::zero(); graph.len()];
+    let mut total = N::zero();
+
+    loop {
+        let mut dist = vec![None; graph.len()];
+        let mut prev = vec![(0, 0); graph.len()];
+        let mut heap = BinaryHeap::new();
+
+        dist[source] = Some(N::zero());
+        heap.push(Reverse((N::zero(), source)));
+
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if dist[u] != Some(d) { continue }
+            for (k, edge) in graph[u].iter().enumerate().filter(|&(_, e)| e.open) {
+                let v = edge.to;
+                let reduced = if edge.backward {
+                    potential[u] - (edge.cost + potential[v])
+                } else {
+                    (potential[u] + edge.cost) - potential[v]
+                };
+                let next = d + reduced;
+                let shorter = match dist[v] {
+                    Some(old) => next < old,
+                    None => true,
+                };
+                if shorter {
+                    dist[v] = Some(next);
+                    prev[v] = (u, k);
+                    heap.push(Reverse((next, v)));
+                }
+            }
+        }
+
+        // No augmenting path left: the flow is maximum
+        if dist[sink].is_none() { break }
+
+        // Nodes that are unreachable now stay unreachable, since augmenting
+        // only opens edges between reachable nodes, so their potentials don't matter
+        for (p, d) in potential.iter_mut().zip(&dist) {
+            if let Some(d) = *d { *p += d }
+        }
+
+        // The source keeps a potential of zero, so the sink's is the cost of the path
+        if let Some(budget) = budget {
+            match total.checked_add(&potential[sink]) {
+                Some(next) if next <= budget => total = next,
+                _ => break,
+            }
+        }
+
+        // Push one unit of flow along the shortest path
+        let mut v = sink;
+        while v != source {
+            let (u, k) = prev[v];
+            graph[u][k].open = false;
+            let rev = graph[u][k].rev;
+            graph[v][rev].open = true;
+            v = u;
+        }
+    }
+
+    (0..height)
+        .map(|i| {
+            graph[i].iter()
+                .find(|e| !e.backward && !e.open)
+                .map(|e| e.to - height)
+        })
+        .collect()
+}
+
+/// Alternative to [`minimize_feasible`](fn.minimize_feasible.html) that formulates
+/// the assignment problem as a minimum cost flow, rather than using Munkres' algorithm.
+///
+/// Each row is connected to a source, each column to a sink, and each row to each
+/// column through an edge of unit capacity, unless the entry is `N::max_value()`.
+/// Augmenting along shortest paths (using Dijkstra's algorithm with potentials)
+/// until the sink is unreachable gives a maximum flow of minimum cost, which
+/// corresponds to a matching of maximum cardinality and, among those, of minimum
+/// total cost. Costs are clamped to be greater or equal to zero.
+///
+/// This solves the same problem as `minimize_feasible` with the same total cost,
+/// although ties may be broken differently. Its running time depends on the number
+/// of available entries instead of the size of the matrix, so it can be faster on
+/// sparse inputs; see the `flow` benchmarks.
+///
+/// Requires the `flow` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_flow;
+///
+/// fn main() {
+///     let x = u32::MAX;
+///     let matrix = vec![
+///         1, 2, 1,
+///         x, x, x,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_flow(&matrix, 3, 3), vec![Some(2), None, Some(0)]);
+/// }
+/// ```
+#[cfg(feature = "flow")]
+#[must_use]
+pub fn minimize_flow<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let entries = (0..height)
+        .flat_map(|i| (0..width).map(move |j| (i, j, matrix[width * i + j])))
+        .filter(|&(_, _, cost)| cost != N::max_value());
+
+    shortest_augmenting_paths(height, width, entries, None)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for sparse problems, where a `None`
+/// entry means that row and column can't be matched.
+///
+/// Finds a matching of maximum cardinality among the `Some` entries, and of
+/// minimum total cost among those. A row is never assigned to a `None` entry;
+/// rows that can't be matched come back as `None`. As in `minimize`, costs are
+/// clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_optional;
+///
+/// fn main() {
+///     let matrix = vec![
+///         Some(1), None,    Some(1),
+///         None,    None,    None,
+///         Some(7), Some(8), None,
+///     ];
+///
+///     assert_eq!(minimize_optional(&matrix, 3, 3), vec![Some(2), None, Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_optional<N>(matrix: &[Option<N>], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    solve_partial(
+        height,
+        width,
+        |i, j| matrix[width * i + j].as_ref().map_or(N::zero(), clamp),
+        |i, j| matrix[width * i + j].is_some(),
+        false,
+    )
+}
+
+/// Solves the assignment problem like [`minimize`](fn.minimize.html), and returns the
+/// matched entries as `(row, col, cost)` triples sorted by increasing cost.
+///
+/// Costs are read from the original matrix, so negative entries are reported as
+/// they are, even though they're clamped while solving. Unassigned rows are left
+/// out, and entries of equal cost are listed in increasing row order.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_sorted;
+///
+/// fn main() {
+///     let matrix = vec![
+///         5, 5,
+///         1, 0,
+///         2, 3,
+///     ];
+///
+///     assert_eq!(minimize_sorted(&matrix, 3, 2), vec![(1, 1, 0), (2, 0, 2)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_sorted<N>(matrix: &[N], height: usize, width: usize) -> Vec<(usize, usize, N)>
+    where N: NumAssign + PrimInt
+{
+    let assignment = minimize(matrix, height, width);
+    let mut edges = assignment_edges(matrix, height, width, &assignment);
+    edges.sort_by_key(|&(_, _, cost)| cost);
+    edges
+}
+
+/// Assigns each point in `a` to a distinct point in `b`, minimizing the total
+/// squared Euclidean distance between matched points.
+///
+/// Returns a vector of length `a.len()`, where entry `i` is the index of the point
+/// in `b` matched to `a[i]`. If `a` has more points than `b`, the extra points are
+/// left unassigned.
+///
+/// Squared distances avoid a square root per pair, and stay exact for integer
+/// coordinates. They're solved with [`minimize_scaled_f64`](fn.minimize_scaled_f64.html),
+/// scaled so that the largest squared distance becomes `2^40`: distances that differ
+/// by more than about `2^-40` of the largest one are always distinguished.
+///
+/// # Panics
+///
+/// Panics if any coordinate is not finite.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_points;
+///
+/// fn main() {
+///     let a = [[0.0, 0.0], [10.0, 0.0]];
+///     let b = [[9.0, 1.0], [1.0, 1.0], [50.0, 50.0]];
+///
+///     assert_eq!(minimize_points(&a, &b), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_points(a: &[[f64; 2]], b: &[[f64; 2]]) -> Vec<Option<usize>> {
+    let squared = |p: &[f64; 2], q: &[f64; 2]| {
+        let (dx, dy) = (p[0] - q[0], p[1] - q[1]);
+        dx * dx + dy * dy
+    };
+
+    let matrix = a.iter()
+        .flat_map(|p| b.iter().map(move |q| squared(p, q)))
+        .collect::<Vec<_>>();
+
+    let max = matrix.iter().cloned().fold(0.0, f64::max);
+    assert!(max.is_finite(), "point coordinates must be finite");
+
+    let scale = if max > 0.0 { (1u64 << 40) as f64 / max } else { 1.0 };
+    minimize_scaled_f64(&matrix, a.len(), b.len(), scale)
+}
+
+/// Why [`minimize_explained`](fn.minimize_explained.html) left a row unassigned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Reason {
+    /// Every column the row could take went to another row, e.g. because
+    /// there are fewer columns than rows.
+    Surplus,
+    /// Every entry in the row is forbidden (`None`), so it can never be matched.
+    Forbidden,
+}
+
+/// Outcome for a single row of [`minimize_explained`](fn.minimize_explained.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RowAssignment {
+    /// The row is assigned to this column.
+    Matched(usize),
+    /// The row is unassigned.
+    Unmatched {
+        /// Why the row couldn't be assigned
+        reason: Reason,
+    },
+}
+
+impl RowAssignment {
+    /// Returns the assigned column, if any.
+    pub fn column(&self) -> Option<usize> {
+        match *self {
+            RowAssignment::Matched(j) => Some(j),
+            RowAssignment::Unmatched { .. } => None,
+        }
+    }
+}
+
+/// Variant of [`minimize_optional`](fn.minimize_optional.html) that explains why
+/// each unassigned row was left out.
+///
+/// A dense matrix can be passed by wrapping every entry in `Some`, in which case
+/// rows are only ever unassigned for lack of columns.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_explained, Reason, RowAssignment};
+///
+/// fn main() {
+///     let matrix = vec![
+///         Some(1), Some(2),
+///         None,    None,
+///         Some(3), Some(1),
+///         Some(2), Some(9),
+///     ];
+///
+///     assert_eq!(
+///         minimize_explained(&matrix, 4, 2),
+///         vec![
+///             RowAssignment::Matched(0),
+///             RowAssignment::Unmatched { reason: Reason::Forbidden },
+///             RowAssignment::Matched(1),
+///             RowAssignment::Unmatched { reason: Reason::Surplus },
+///         ]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_explained<N>(matrix: &[Option<N>], height: usize, width: usize) -> Vec<RowAssignment>
+    where N: NumAssign + PrimInt
+{
+    minimize_optional(matrix, height, width)
+        .into_iter()
+        .enumerate()
+        .map(|(i, j)| match j {
+            Some(j) => RowAssignment::Matched(j),
+            None if matrix[width * i..width * (i + 1)].iter().all(Option::is_none) => {
+                RowAssignment::Unmatched { reason: Reason::Forbidden }
+            }
+            None => RowAssignment::Unmatched { reason: Reason::Surplus },
+        })
+        .collect()
+}
+
+/// Summary statistics of a cost matrix, as computed by [`analyze`](fn.analyze.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatrixStats<N> {
+    /// Smallest entry, or `None` if the matrix is empty
+    pub min: Option<N>,
+    /// Largest entry, or `None` if the matrix is empty
+    pub max: Option<N>,
+    /// Number of entries equal to zero
+    pub zeros: usize,
+    /// Number of rows without a zero entry
+    pub rows_without_zero: usize,
+    /// Number of columns without a zero entry
+    pub cols_without_zero: usize,
+    /// Whether `height == width`
+    pub square: bool,
+}
+
+/// Computes summary statistics of a `height * width` cost matrix, without solving it.
+///
+/// This is a cheap `O(height * width)` pass that helps with spotting encoding
+/// mistakes before solving, like a row consisting entirely of sentinel values
+/// (where `min` or `max` is unexpected), and with gauging difficulty: the fewer
+/// rows and columns without a zero, the less work is left after the initial
+/// reductions. Entries are reported as they are, without clamping.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{analyze, MatrixStats};
+///
+/// fn main() {
+///     let matrix = vec![
+///         0, 2, 1,
+///         4, 5, 0,
+///     ];
+///
+///     assert_eq!(analyze(&matrix, 2, 3), MatrixStats {
+///         min: Some(0),
+///         max: Some(5),
+///         zeros: 2,
+///         rows_without_zero: 0,
+///         cols_without_zero: 1,
+///         square: false,
+///     });
+/// }
+/// ```
+pub fn analyze<N: PrimInt>(matrix: &[N], height: usize, width: usize) -> MatrixStats<N> {
+    let entries = &matrix[..height * width];
+
+    let rows_without_zero = (0..height)
+        .filter(|&i| entries[width * i..width * (i + 1)].iter().all(|cost| !cost.is_zero()))
+        .count();
+
+    let cols_without_zero = (0..width)
+        .filter(|&j| (0..height).all(|i| !entries[width * i + j].is_zero()))
+        .count();
+
+    MatrixStats {
+        min: entries.iter().cloned().min(),
+        max: entries.iter().cloned().max(),
+        zeros: entries.iter().filter(|cost| cost.is_zero()).count(),
+        rows_without_zero,
+        cols_without_zero,
+        square: height == width,
+    }
+}
+
+/// Column left unused by an assignment, as reported by
+/// [`minimize_utilization`](fn.minimize_utilization.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnusedColumn<N> {
+    /// Index of the column
+    pub column: usize,
+    /// Cheapest entry of the column, i.e. what it would cost to use it
+    pub min_cost: N,
+    /// First row with that cheapest entry
+    pub cheapest_row: usize,
+}
+
+/// Utilization of the columns by an optimal assignment, as computed by
+/// [`minimize_utilization`](fn.minimize_utilization.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UtilizationReport<N> {
+    /// Matched `(row, column, cost)` edges, in increasing row order
+    pub edges: Vec<(usize, usize, N)>,
+    /// Columns without a matched row, in increasing column order
+    pub unused: Vec<UnusedColumn<N>>,
+}
+
+/// Solves `matrix` as in [`minimize`](fn.minimize.html), and reports which columns
+/// are used, and what the unused ones would cost.
+///
+/// This is mostly useful when `width > height`, where `width - height` columns
+/// are always left over: their cheapest entries show how much slack is left, and
+/// where. Costs are reported as they are in `matrix`, without clamping. A matrix
+/// without rows has no costs to report, so its report is empty.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_utilization, UnusedColumn};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 5, 3, 8,
+///         4, 2, 6, 9,
+///     ];
+///
+///     let report = minimize_utilization(&matrix, 2, 4);
+///     assert_eq!(report.edges, vec![(0, 0, 1), (1, 1, 2)]);
+///     assert_eq!(report.unused, vec![
+///         UnusedColumn { column: 2, min_cost: 3, cheapest_row: 0 },
+///         UnusedColumn { column: 3, min_cost: 8, cheapest_row: 0 },
+///     ]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_utilization<N>(matrix: &[N], height: usize, width: usize) -> UtilizationReport<N>
+    where N: NumAssign + PrimInt
+{
+    let assignment = minimize(matrix, height, width);
+    let edges = assignment_edges(matrix, height, width, &assignment);
+
+    if height == 0 {
+        return UtilizationReport { edges, unused: Vec::new() }
+    }
+
+    let mut used = FixedBitSet::with_capacity(width);
+    assignment.iter().flatten().for_each(|&j| used.insert(j));
+
+    let unused = (0..width)
+        .filter(|&j| off!(used, j))
+        .map(|j| {
+            let cheapest_row = argmin((0..height).map(|i| matrix[width * i + j])).unwrap();
+            UnusedColumn { column: j, min_cost: matrix[width * cheapest_row + j], cheapest_row }
+        })
+        .collect();
+
+    UtilizationReport { edges, unused }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) where any row or column may be left
+/// unmatched for a fixed penalty, so the matching doesn't have to be maximal.
+///
+/// Every unassigned row costs `row_slack`, and every unused column costs `col_slack`.
+/// This minimizes the total of the matched costs and the penalties, trading off
+/// matching an expensive entry against paying slack for both its row and column.
+/// Returns the assignment together with that total. As in `minimize`, costs and
+/// penalties are clamped to be greater or equal to zero.
+///
+/// Internally, each row gets a private dummy column of cost `row_slack`, each
+/// column a private dummy row of cost `col_slack`, and the resulting square
+/// `(height + width) * (width + height)` problem is solved with every other
+/// dummy entry forbidden, except dummy rows against dummy columns, which are free.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_slack;
+///
+/// fn main() {
+///     let matrix = vec![
+///          1, 100,
+///         100,  1,
+///         100, 100,
+///     ];
+///
+///     // Matching row 2 would cost 100 plus leaving out another row, so
+///     // it's cheaper to leave it unmatched for a penalty of 10.
+///     assert_eq!(
+///         minimize_with_slack(&matrix, 3, 2, 10, 0),
+///         (vec![Some(0), Some(1), None], 12)
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_slack<N>(matrix: &[N], height: usize, width: usize, row_slack: N, col_slack: N) -> (Vec<Option<usize>>, N)
+    where N: NumAssign + PrimInt
+{
+    let n = height + width;
+    let (row_slack, col_slack) = (clamp(&row_slack), clamp(&col_slack));
+
+    let mut m = Array2::zeros((n, n));
+    let mut mask = Array2::from_elem((n, n), true);
+
+    for i in 0..height {
+        for j in 0..width {
+            set!(m, i, j, clamp(&matrix[width * i + j]));
+        }
+        for k in 0..height {
+            set!(m, i, width + k, row_slack);
+            set!(mask, i, width + k, i == k);
+        }
+    }
+
+    for k in 0..width {
+        for j in 0..width {
+            set!(m, height + k, j, col_slack);
+            set!(mask, height + k, j, j == k);
+        }
+    }
+
+    // The padded problem can always be matched along the dummy diagonals
+    let stars = solve(&mut m, Some(&mask)).unwrap();
+
+    let result = (0..height)
+        .map(|i| (0..width).find(|&j| get!(stars, i, j)))
+        .collect::<Vec<_>>();
+
+    let matched = result.iter().filter(|j| j.is_some()).count();
+    let slack = (0..height - matched).fold(N::zero(), |acc, _| acc + row_slack)
+        + (0..width - matched).fold(N::zero(), |acc, _| acc + col_slack);
+
+    let cost = total_cost(matrix, width, &result) + slack;
+    (result, cost)
+}
+
+/// State of the algorithm when entering a step, as recorded by
+/// [`minimize_trace`](fn.minimize_trace.html).
+///
+/// Everything is in the original orientation of the matrix: `costs`, `stars` and
+/// `primes` have shape `(height, width)`, `row_cover` has `height` entries, and
+/// `col_cover` has `width` entries.
+///
+/// Requires the `trace` feature.
+#[cfg(feature = "trace")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot<N> {
+    /// The step being entered, numbered 1 through 6 as in the
+    /// [outline](http://csclab.murraystate.edu/~bob.pilgrim/445/munkres.html)
+    pub step: u8,
+    /// The current reduced costs
+    pub costs: Array2<N>,
+    /// The set of starred zeros
+    pub stars: Array2<bool>,
+    /// The set of primed zeros
+    pub primes: Array2<bool>,
+    /// Whether each row is covered
+    pub row_cover: Vec<bool>,
+    /// Whether each column is covered
+    pub col_cover: Vec<bool>,
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also records the state of the
+/// algorithm every time it enters a step, for visualizing how it runs.
+///
+/// This always runs the full algorithm, even for inputs `minimize` special-cases,
+/// such as a single row. Each snapshot clones the whole state, so memory use is
+/// `O(iterations * height * width)`, which makes this unsuitable for anything
+/// but small, illustrative inputs.
+///
+/// Requires the `trace` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_trace;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 4,
+///     ];
+///
+///     let (assignment, trace) = minimize_trace(&matrix, 2, 2);
+///     assert_eq!(assignment, vec![Some(1), Some(0)]);
+///
+///     let steps = trace.iter().map(|s| s.step).collect::<Vec<_>>();
+///     assert_eq!(steps, vec![1, 2, 3, 4, 6, 4, 4, 5, 3]);
+/// }
+/// ```
+#[cfg(feature = "trace")]
+#[must_use]
+pub fn minimize_trace<N>(matrix: &[N], height: usize, width: usize) -> (Vec<Option<usize>>, Vec<Snapshot<N>>)
+    where N: NumAssign + PrimInt
+{
+    // No possible assignment
+    if height == 0 || width == 0 { return (vec![None; height], Vec::new()) }
+
+    let transposed = width < height;
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+    let (h, w) = m.dim();
+
+    let mut stars = Array2::from_elem((h, w), false);
+    let mut primes = Array2::from_elem((h, w), false);
+    let mut row_cover = FixedBitSet::with_capacity(h);
+    let mut col_cover = FixedBitSet::with_capacity(w);
+    let mut columns = Vec::with_capacity(w);
+
+    let mut trace = Vec::new();
+    let mut observe = |step,
+                       m: &Array2<N>,
+                       stars: &Array2<bool>,
+                       primes: &Array2<bool>,
+                       rows: &FixedBitSet,
+                       cols: &FixedBitSet| {
+        let rows = (0..h).map(|i| on!(rows, i)).collect();
+        let cols = (0..w).map(|j| on!(cols, j)).collect();
+        let (row_cover, col_cover) = if transposed { (cols, rows) } else { (rows, cols) };
+        trace.push(Snapshot {
+            step,
+            costs: unorient(m, height, width),
+            stars: unorient(stars, height, width),
+            primes: unorient(primes, height, width),
+            row_cover,
+            col_cover,
+        });
+    };
+
+    // Without forbidden entries, every row can always be matched
+    solve_in(&mut m, None, &mut stars, &mut primes, &mut row_cover, &mut col_cover, &mut columns, &mut observe);
+    (assignment(&stars, transposed), trace)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for sparse matrices in compressed
+/// sparse row (CSR) format, where absent entries are forbidden.
+///
+/// # Takes
+///
+/// - `values`: the present entries, in row-major order
+/// - `col_indices`: the column of each entry in `values`
+/// - `row_ptr`: `height + 1` offsets, where row `i` holds the entries in
+///   `row_ptr[i]..row_ptr[i + 1]`
+/// - `height`, `width`: dimensions of the matrix
+///
+/// # Returns
+///
+/// A matching of maximum cardinality among the present entries, and of minimum
+/// total cost among those, in the same format as `minimize`. Rows that can't be
+/// matched are left as `None`. As in `minimize`, costs are clamped to be greater
+/// or equal to zero.
+///
+/// This never builds the dense matrix. It finds shortest augmenting paths over
+/// the present entries alone, in `O(r * e * log(height + width))` time and
+/// `O(height + width + e)` space for `e` entries and `r` matched rows. For a
+/// 10,000 x 10,000 matrix that's 99% forbidden, that's one million entries in
+/// place of the hundred million a dense workspace would need.
+///
+/// # Panics
+///
+/// Panics if `row_ptr` doesn't have `height + 1` non-decreasing offsets ending at
+/// `values.len()`, if `col_indices` and `values` have different lengths, or if a
+/// column index is out of bounds.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_csr;
+///
+/// fn main() {
+///     // 1 . 1
+///     // . . .
+///     // 7 8 .
+///     let values = vec![1, 1, 7, 8];
+///     let col_indices = vec![0, 2, 0, 1];
+///     let row_ptr = vec![0, 2, 2, 4];
+///
+///     assert_eq!(
+///         minimize_csr(&values, &col_indices, &row_ptr, 3, 3),
+///         vec![Some(2), None, Some(0)]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_csr<N>(values: &[N], col_indices: &[usize], row_ptr: &[usize], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(row_ptr.len(), height + 1, "row_ptr must have height + 1 entries");
+    assert_eq!(col_indices.len(), values.len(), "col_indices and values must have the same length");
+    assert_eq!(row_ptr[height], values.len(), "row_ptr must end at values.len()");
+    assert!(row_ptr.windows(2).all(|w| w[0] <= w[1]), "row_ptr must be non-decreasing");
+    assert!(col_indices.iter().all(|&j| j < width), "column index out of bounds");
+
+    let entries = (0..height).flat_map(|i| {
+        (row_ptr[i]..row_ptr[i + 1]).map(move |k| (i, col_indices[k], values[k]))
+    });
+
+    shortest_augmenting_paths(height, width, entries, None)
+}
+
+/// Finds the largest matching whose total cost is at most `budget`, and of
+/// minimum total cost among those. Rows left out are `None`.
+///
+/// This is exact, not an approximation: the cheapest matching of each size
+/// costs at least as much as the cheapest matching one row smaller, so the
+/// matchings are grown one shortest augmenting path at a time, and growth stops
+/// before the first one that would go over the budget. Runs in
+/// `O(r * height * width * log(height + width))` time for `r` matched rows.
+/// As in `minimize`, costs are clamped to be greater or equal to zero, so a
+/// negative budget matches no rows.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::maximize_cardinality_under_budget;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 9, 9,
+///         9, 2, 9,
+///         9, 9, 8,
+///     ];
+///
+///     assert_eq!(maximize_cardinality_under_budget(&matrix, 3, 3, 11), vec![Some(0), Some(1), Some(2)]);
+///     assert_eq!(maximize_cardinality_under_budget(&matrix, 3, 3, 10), vec![Some(0), Some(1), None]);
+/// }
+/// ```
+#[must_use]
+pub fn maximize_cardinality_under_budget<N>(matrix: &[N], height: usize, width: usize, budget: N) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let matrix = &matrix[..height * width];
+    let entries = (0..height)
+        .flat_map(|i| (0..width).map(move |j| (i, j, matrix[width * i + j])));
+
+    shortest_augmenting_paths(height, width, entries, Some(budget))
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that yields `(row, column)` pairs
+/// lazily, instead of collecting them into a vector.
+///
+/// The assignment is still solved eagerly, but each row's column is only looked up
+/// from the final starred zeros when it's reached, so no result vector is allocated.
+/// Pairs are yielded in increasing row order, one for each of the `height` rows,
+/// with the same columns `minimize` would return.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_iter;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 4,
+///         0, 9,
+///     ];
+///
+///     let pairs = minimize_iter(&matrix, 3, 2).collect::<Vec<_>>();
+///     assert_eq!(pairs, vec![(0, Some(1)), (1, None), (2, Some(0))]);
+/// }
+/// ```
+pub fn minimize_iter<N>(matrix: &[N], height: usize, width: usize) -> impl Iterator<Item = (usize, Option<usize>)>
+    where N: NumAssign + PrimInt
+{
+    let transposed = width < height;
+    let mut m = orient(height, width, |i, j| clamp(&matrix[width * i + j]));
+
+    // Without forbidden entries, every row can always be matched
+    let stars = solve(&mut m, None).unwrap();
+
+    // Rows of a transposed workspace are original columns
+    (0..height).map(move |i| {
+        let stars = if transposed { stars.column(i) } else { stars.row(i) };
+        (i, stars.iter().position(|&star| star))
+    })
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that stores costs as `I`, but
+/// runs the algorithm on a wider type `A`.
+///
+/// The reduced costs can grow beyond the largest input cost while solving, so
+/// arithmetic in the input type can overflow even when every input fits. This
+/// widens each entry into `A` as the workspace is filled, keeping the matrix
+/// itself compact without a separate widened copy. As in `minimize`, costs are
+/// clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_as;
+///
+/// fn main() {
+///     let matrix: Vec<u16> = vec![
+///         65535,     0,     1,
+///             0, 65535, 65535,
+///             1, 65534, 65534,
+///     ];
+///
+///     assert_eq!(minimize_as::<u16, u64>(&matrix, 3, 3), vec![Some(1), Some(0), Some(2)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_as<I, A>(matrix: &[I], height: usize, width: usize) -> Vec<Option<usize>>
+    where I: Copy + Into<A>,
+          A: NumAssign + PrimInt,
+{
+    minimize_by(height, width, |i, j| clamp(&matrix[width * i + j].into()))
+}
+
+/// Maps an optimal `assignment` for `matrix` to a canonical representative,
+/// so that any two optimal assignments of the same problem compare equal.
+///
+/// Ties between optimal solutions are broken by [`minimize`](fn.minimize.html)
+/// in whatever order the algorithm happens to visit entries, which can change
+/// between versions. This instead returns the lexicographically smallest
+/// optimal assignment, compared row by row, where `None` orders after every
+/// column. It runs a second pass over the optimal-cost entries only, taking
+/// `O(n^4)` time in the worst case, so it's meant for tests and diagnostics.
+///
+/// # Panics
+///
+/// Panics if `assignment` isn't optimal, as determined by
+/// [`is_optimal`](fn.is_optimal.html).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::canonicalize;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 1, 2,
+///         1, 1, 2,
+///         2, 2, 0,
+///     ];
+///
+///     let canonical = vec![Some(0), Some(1), Some(2)];
+///     assert_eq!(canonicalize(&[Some(0), Some(1), Some(2)], &matrix, 3, 3), canonical);
+///     assert_eq!(canonicalize(&[Some(1), Some(0), Some(2)], &matrix, 3, 3), canonical);
+/// }
+/// ```
+pub fn canonicalize<N>(assignment: &[Option<usize>], matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert!(
+        is_optimal(matrix, height, width, assignment),
+        "assignment is not optimal for the given matrix"
+    );
+    if height == 0 || width == 0 { return vec![None; height] }
+    solve_partial(height, width, |i, j| clamp(&matrix[width * i + j]), |_, _| true, true)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) with a lexicographic objective:
+/// among all assignments of minimum total `cost`, returns one that maximizes
+/// the total of `secondary`, a second matrix of the same shape.
+///
+/// The problem is padded to a square one, so that every optimal matching only
+/// uses entries with zero reduced cost once the first pass is done. The second
+/// pass solves for `secondary` restricted to those entries. As in `minimize`,
+/// entries of `cost` are clamped to be greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if `secondary` is shorter than `height * width`, or if it can't be
+/// transformed into costs as in [`maximize`](fn.maximize.html). See
+/// [`try_minimize_then_maximize`](fn.try_minimize_then_maximize.html) for a
+/// fallible variant.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_then_maximize;
+///
+/// fn main() {
+///     let cost = vec![
+///         1, 1, 5,
+///         1, 1, 5,
+///         5, 5, 1,
+///     ];
+///
+///     let secondary = vec![
+///         0, 2, 9,
+///         3, 0, 9,
+///         9, 9, 0,
+///     ];
+///
+///     assert_eq!(
+///         minimize_then_maximize(&cost, &secondary, 3, 3),
+///         vec![Some(1), Some(0), Some(2)]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_then_maximize<N>(cost: &[N], secondary: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    try_minimize_then_maximize(cost, secondary, height, width)
+        .expect("minimize_then_maximize: secondary range exceeds the cost type")
+}
+
+/// Fallible variant of [`minimize_then_maximize`](fn.minimize_then_maximize.html).
+///
+/// # Errors
+///
+/// Returns an `OverflowError` identifying the first entry of `secondary` that
+/// can't be transformed, as in [`try_maximize`](fn.try_maximize.html).
+pub fn try_minimize_then_maximize<N>(cost: &[N], secondary: &[N], height: usize, width: usize) -> Result<Vec<Option<usize>>, OverflowError>
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 { return Ok(vec![None; height]) }
+
+    let secondary = &secondary[..height * width];
+    let max = *secondary.iter().max().unwrap();
+    let n = height.max(width);
+    let tight = tight_entries(cost, height, width);
+
+    // Every such matching uses exactly `min(height, width)` original entries,
+    // so padding with zero doesn't change which one is best.
+    let mut m = Array2::zeros((n, n));
+    for i in 0..height {
+        for j in 0..width {
+            let value = max.checked_sub(&secondary[width * i + j])
+                .ok_or(OverflowError { row: i, col: j })?;
+            set!(m, i, j, value);
+        }
+    }
+
+    let stars = solve(&mut m, Some(&tight))
+        .expect("tight entries always have a perfect matching");
+
+    Ok((0..height)
+        .map(|i| (0..width).find(|&j| get!(stars, i, j)))
+        .collect())
+}
+
+/// Pads `cost` into a square `n * n` problem with zeros, for `n = max(height, width)`,
+/// and returns the entries with a zero reduced cost at its optimum.
+///
+/// Every perfect matching of the padded problem on these entries has minimum cost,
+/// and restricted to the original entries, is a minimum cost maximal matching.
+fn tight_entries<N: NumAssign + PrimInt>(cost: &[N], height: usize, width: usize) -> Array2<bool> {
+    let n = height.max(width);
+    let mut m = Array2::from_shape_fn((n, n), |(i, j)| {
+        if i < height && j < width { clamp(&cost[width * i + j]) } else { N::zero() }
+    });
+
+    // Square problem always has a perfect matching
+    solve(&mut m, None).unwrap();
+    m.map(|c| c.is_zero())
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for re-planning: among all minimum
+/// cost assignments, returns one that changes the fewest rows from `reference`.
+///
+/// A row counts as changed if its entry differs from `reference`, including
+/// when exactly one of them is `None`. The matrix is first solved as in
+/// `minimize`, to find the entries that appear in some optimal assignment.
+/// Then a second assignment over those entries alone minimizes the number of
+/// changed rows. As in `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if `reference` doesn't have exactly `height` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_min_churn;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 1, 5,
+///         1, 1, 5,
+///         5, 5, 1,
+///     ];
+///
+///     let current = [Some(1), Some(0), Some(2)];
+///     assert_eq!(minimize_min_churn(&matrix, 3, 3, &current), current);
+///
+///     // Row 2 has to move, but rows 0 and 1 can stay put
+///     let current = [Some(1), Some(0), Some(0)];
+///     assert_eq!(minimize_min_churn(&matrix, 3, 3, &current), vec![Some(1), Some(0), Some(2)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_min_churn<N>(matrix: &[N], height: usize, width: usize, reference: &[Option<usize>]) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(reference.len(), height, "reference must have one entry per row");
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    let n = height.max(width);
+    let tight = tight_entries(matrix, height, width);
+
+    // Dummy columns stand for leaving a row unassigned
+    let mut churn = Array2::from_shape_fn((n, n), |(i, j)| {
+        if i >= height { return 0u32 }
+        let kept = if j < width { reference[i] == Some(j) } else { reference[i].is_none() };
+        if kept { 0 } else { 1 }
+    });
+
+    let stars = solve(&mut churn, Some(&tight))
+        .expect("tight entries always have a perfect matching");
+
+    (0..height)
+        .map(|i| (0..width).find(|&j| get!(stars, i, j)))
+        .collect()
+}
+
+/// Returns every distinct minimum cost assignment of a small matrix, in the
+/// format of [`minimize`](fn.minimize.html), sorted in lexicographic order.
+///
+/// The number of optima grows factorially with the size of the matrix: every one
+/// of the `8! = 40320` permutations of a constant `8 * 8` matrix is optimal, for
+/// instance. So this only accepts matrices of at most 8 rows and columns, and is
+/// meant for teaching, and for choosing among optima by criteria the solver
+/// doesn't know about. The matrix is solved once to find the entries that appear
+/// in some optimal assignment, and every matching of those entries is then
+/// enumerated. As in `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// # Panics
+///
+/// Panics if `height > 8` or `width > 8`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::all_optima;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 1,
+///     ];
+///     assert_eq!(all_optima(&matrix, 2, 2), vec![vec![Some(0), Some(1)]]);
+///
+///     let matrix = vec![
+///         1, 1,
+///         1, 1,
+///     ];
+///     assert_eq!(all_optima(&matrix, 2, 2), vec![
+///         vec![Some(0), Some(1)],
+///         vec![Some(1), Some(0)],
+///     ]);
+/// }
+/// ```
+#[must_use]
+pub fn all_optima<N>(matrix: &[N], height: usize, width: usize) -> Vec<Vec<Option<usize>>>
+    where N: NumAssign + PrimInt
+{
+    assert!(height <= 8 && width <= 8, "all_optima supports at most 8 rows and columns, found {} x {}", height, width);
+    if height == 0 || width == 0 { return vec![vec![None; height]] }
+
+    // Every perfect matching of the padded problem on tight entries is optimal.
+    // Dummy rows and columns are interchangeable, so several such matchings
+    // can give the same assignment.
+    fn enumerate(
+        tight: &Array2<bool>,
+        i: usize,
+        used: &mut FixedBitSet,
+        current: &mut Vec<usize>,
+        found: &mut Vec<Vec<usize>>,
+    ) {
+        let n = tight.nrows();
+        if i == n {
+            found.push(current.clone());
+            return
+        }
+        for j in 0..n {
+            if on!(used, j) || !get!(tight, i, j) { continue }
+            used.insert(j);
+            current.push(j);
+            enumerate(tight, i + 1, used, current, found);
+            current.pop();
+            used.set(j, false);
+        }
+    }
+
+    let tight = tight_entries(matrix, height, width);
+    let mut found = Vec::new();
+    enumerate(&tight, 0, &mut FixedBitSet::with_capacity(tight.nrows()), &mut Vec::new(), &mut found);
+
+    let mut optima = found.into_iter()
+        .map(|columns| {
+            columns[..height].iter()
+                .map(|&j| if j < width { Some(j) } else { None })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    optima.sort_unstable();
+    optima.dedup();
+    optima
+}
+
+/// Returns a conservative bound on the entries of a `height * width` matrix of
+/// type `N`, such that solving any matrix with every entry at most this value
+/// cannot overflow.
+///
+/// Two kinds of values grow past the original entries. While solving, Step 6
+/// adds to entries covered twice, which can push a reduced cost up to about
+/// twice the largest entry `c`. And the total cost of `min(height, width)`
+/// assigned entries, as returned by e.g. [`minimize_cost_only`](fn.minimize_cost_only.html),
+/// can reach `min(height, width) * c`. This returns
+/// `N::max_value() / (2 * min(height, width))`, which covers both with a factor
+/// of `min(height, width)` to spare.
+///
+/// Useful for picking a sentinel for undesirable entries: anything up to this
+/// value is safe, regardless of the other entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{max_safe_cost, minimize_cost_only};
+///
+/// fn main() {
+///     let cap = max_safe_cost::<u8>(4, 4);
+///     assert_eq!(cap, 31);
+///
+///     let matrix = vec![cap; 16];
+///     assert_eq!(minimize_cost_only(&matrix, 4, 4), 4 * cap);
+/// }
+/// ```
+pub fn max_safe_cost<N: NumAssign + PrimInt>(height: usize, width: usize) -> N {
+    let k = height.min(width);
+    if k == 0 { return N::max_value() }
+    match N::from(2 * k) {
+        Some(divisor) => N::max_value() / divisor,
+        None => N::zero(),
+    }
+}
+
+/// Solves the independent blocks of a block-diagonal problem, each given as a
+/// `(matrix, height, width)` triple in the format of [`minimize`](fn.minimize.html).
+///
+/// Returns one assignment per block, indexed within that block. When no entry
+/// outside the blocks may be matched, e.g. because they're forbidden or far more
+/// expensive, this gives the same total cost as solving the whole matrix, but
+/// the work for each block only grows with its own size, rather than with the
+/// size of the whole matrix. With the `rayon` feature enabled, the blocks are
+/// solved in parallel. As in `minimize`, costs are clamped to be greater or equal
+/// to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_blocks;
+///
+/// fn main() {
+///     let blocks = vec![
+///         (vec![1, 2, 2, 1], 2, 2),
+///         (vec![5, 3, 4], 1, 3),
+///     ];
+///
+///     assert_eq!(
+///         minimize_blocks(&blocks),
+///         vec![vec![Some(0), Some(1)], vec![Some(1)]]
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_blocks<N>(blocks: &[(Vec<N>, usize, usize)]) -> Vec<Vec<Option<usize>>>
+    where N: NumAssign + PrimInt + Send + Sync
+{
+    let solve_block = |&(ref matrix, height, width): &(Vec<N>, usize, usize)| minimize(matrix, height, width);
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        blocks.par_iter().map(solve_block).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    blocks.iter().map(solve_block).collect()
+}
+
+/// Finds the `k` cheapest maximal matchings for `matrix`, in order of
+/// non-decreasing total cost, using Murty's algorithm.
+///
+/// Returns up to `k` pairs of an assignment, in the format of
+/// [`minimize`](fn.minimize.html), and its total cost. Fewer are returned
+/// if the matrix has fewer than `k` distinct maximal matchings. As in
+/// `minimize`, costs are clamped to be greater or equal to zero.
+///
+/// Each solution found splits the remaining ones into one subproblem per
+/// assigned entry, which forbids that entry and locks in every assigned entry
+/// before it. The subproblems are independent, so with the `rayon` feature
+/// enabled, they're solved in parallel. Either way, solutions of equal cost are
+/// returned in the same order.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_k_best;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 3,
+///         2, 4, 6,
+///         3, 6, 9,
+///     ];
+///
+///     let best = minimize_k_best(&matrix, 3, 3, 3);
+///     assert_eq!(best[0], (vec![Some(2), Some(1), Some(0)], 10));
+///     assert_eq!(best[1].1, 11);
+///     assert_eq!(best[2].1, 11);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_k_best<N>(matrix: &[N], height: usize, width: usize, k: usize) -> Vec<(Vec<Option<usize>>, N)>
+    where N: NumAssign + PrimInt + Send + Sync
+{
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    let mut best = Vec::new();
+    if k == 0 { return best }
+    if height == 0 || width == 0 {
+        best.push((vec![None; height], N::zero()));
+        return best
+    }
+
+    let matrix = &matrix[..height * width];
+    let rows = height.min(width);
+
+    // Optimal assignment subject to `allowed`, if `rows` rows can still be matched
+    let solve_within = |allowed: &Array2<bool>| {
+        let assignment = solve_partial(
+            height,
+            width,
+            |i, j| clamp(&matrix[width * i + j]),
+            |i, j| get!(allowed, i, j),
+            false,
+        );
+        if assignment.iter().filter(|j| j.is_some()).count() < rows { return None }
+        let cost = total_cost(matrix, width, &assignment);
+        Some((cost, assignment))
+    };
+
+    // Subproblems are ordered by cost, then by index into `nodes`,
+    // so that ties don't depend on scheduling.
+    let mut heap = BinaryHeap::new();
+    let mut nodes = Vec::new();
+    let root = Array2::from_elem((height, width), true);
+    if let Some((cost, assignment)) = solve_within(&root) {
+        heap.push(Reverse((cost, nodes.len())));
+        nodes.push(Some((assignment, root)));
+    }
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        let (assignment, allowed) = nodes[index].take().unwrap();
+        let assigned = assignment.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| (i, j)))
+            .collect::<Vec<_>>();
+
+        best.push((assignment, cost));
+        if best.len() == k { break }
+
+        // Subproblem `t` forbids the `t`th assigned entry, and locks in
+        // every assigned entry before it.
+        let partition = |t: usize| {
+            let mut allowed = allowed.clone();
+            for &(i, j) in &assigned[..t] {
+                allowed.row_mut(i).fill(false);
+                allowed.column_mut(j).fill(false);
+                set!(allowed, i, j, true);
+            }
+            let (i, j) = assigned[t];
+            set!(allowed, i, j, false);
+            solve_within(&allowed).map(|(cost, assignment)| (cost, assignment, allowed))
+        };
+
+        #[cfg(feature = "rayon")]
+        let children = {
+            use rayon::prelude::*;
+            (0..assigned.len()).into_par_iter().map(partition).collect::<Vec<_>>()
+        };
+
+        #[cfg(not(feature = "rayon"))]
+        let children = (0..assigned.len()).map(partition).collect::<Vec<_>>();
+
+        for (cost, assignment, allowed) in children.into_iter().flatten() {
+            heap.push(Reverse((cost, nodes.len())));
+            nodes.push(Some((assignment, allowed)));
+        }
+    }
+
+    best
+}
+
+/// Returns whether `matrix` has exactly one optimal assignment, i.e. whether
+/// the result of [`minimize`](fn.minimize.html) doesn't depend on how ties
+/// are broken.
+///
+/// This asks [`minimize_k_best`](fn.minimize_k_best.html) for the two cheapest
+/// maximal matchings, and checks that the second costs strictly more than the
+/// first, which takes up to `min(height, width)` extra solves. As in `minimize`,
+/// costs are clamped to be greater or equal to zero, so two assignments that only
+/// differ in negative entries are ties.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::is_unique_optimum;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         2, 1,
+///     ];
+///     assert!(is_unique_optimum(&matrix, 2, 2));
+///
+///     let matrix = vec![
+///         1, 2,
+///         2, 3,
+///     ];
+///     assert!(!is_unique_optimum(&matrix, 2, 2));
+/// }
+/// ```
+#[must_use]
+pub fn is_unique_optimum<N>(matrix: &[N], height: usize, width: usize) -> bool
+    where N: NumAssign + PrimInt + Send + Sync
+{
+    let best = minimize_k_best(matrix, height, width, 2);
+    best.len() < 2 || best[1].1 > best[0].1
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also folds the costs of the
+/// assigned entries with `combine`, starting from `identity`.
+///
+/// Munkres' algorithm fundamentally optimizes additive objectives, so the
+/// assignment returned is always one of minimum *sum*, and it's only guaranteed
+/// to minimize the aggregate when `combine` is addition (e.g. to report the total
+/// in a wider type, or with saturation). Other objectives need to be transformed
+/// into additive ones first: see [`minimize_product`](fn.minimize_product.html)
+/// for products. As in `minimize`, costs are clamped to be greater or equal to zero,
+/// and entries are combined in row order.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_aggregate;
+///
+/// fn main() {
+///     let matrix: Vec<u8> = vec![
+///         200, 100,
+///         100, 200,
+///     ];
+///
+///     let (assignment, total) = minimize_aggregate(&matrix, 2, 2, |a: u8, b| a.saturating_add(b), 0);
+///     assert_eq!(assignment, vec![Some(1), Some(0)]);
+///     assert_eq!(total, 200);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_aggregate<N, F>(matrix: &[N], height: usize, width: usize, combine: F, identity: N) -> (Vec<Option<usize>>, N)
+    where N: NumAssign + PrimInt,
+          F: Fn(N, N) -> N,
+{
+    let assignment = minimize(matrix, height, width);
+    let aggregate = assignment.iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| clamp(&matrix[width * i + j])))
+        .fold(identity, combine);
+    (assignment, aggregate)
+}
+
+/// Variant of [`minimize_scaled_f64`](fn.minimize_scaled_f64.html) that minimizes
+/// the *product* of the assigned entries, e.g. to find the most likely assignment
+/// given independent probabilities of error.
+///
+/// Since `ln` is increasing and turns products into sums, minimizing the sum of
+/// `ln(c)` minimizes the product. Every maximal matching assigns the same number
+/// of entries, so the logarithms can be shifted to be non-negative without changing
+/// the optimum, and are then scaled so the largest is `2^40`.
+///
+/// # Panics
+///
+/// Panics if any entry is not finite and strictly positive.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_product;
+///
+/// fn main() {
+///     // The sum is minimized by the anti-diagonal (3 + 4 = 7, rather than 1 + 9 = 10)...
+///     let matrix = vec![
+///         1.0, 3.0,
+///         4.0, 9.0,
+///     ];
+///
+///     // ...but the product by the diagonal (1 * 9 = 9, rather than 3 * 4 = 12).
+///     assert_eq!(minimize_product(&matrix, 2, 2), vec![Some(0), Some(1)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_product(matrix: &[f64], height: usize, width: usize) -> Vec<Option<usize>> {
+    let logs = matrix[..height * width].iter()
+        .map(|&cost| {
+            assert!(cost.is_finite() && cost > 0.0, "cost {} must be finite and positive", cost);
+            cost.ln()
+        })
+        .collect::<Vec<_>>();
+
+    minimize_logs(&logs, height, width)
+}
+
+/// Minimizes the sum of `logs`, after shifting them to be non-negative and
+/// scaling the largest to `2^40`, as described in `minimize_product`.
+fn minimize_logs(logs: &[f64], height: usize, width: usize) -> Vec<Option<usize>> {
+    let min = logs.iter().cloned().fold(f64::INFINITY, f64::min);
+    let shifted = logs.iter().map(|&log| log - min).collect::<Vec<_>>();
+    let max = shifted.iter().cloned().fold(0.0, f64::max);
+
+    let scale = if max > 0.0 { (1u64 << 40) as f64 / max } else { 1.0 };
+    minimize_scaled_f64(&shifted, height, width, scale)
+}
+
+/// Finds the maximum a posteriori assignment of a likelihood matrix: the one
+/// maximizing the *product* of the assigned entries, as in data association.
+///
+/// Maximizing the product is the same as minimizing the sum of negative logs,
+/// which are then shifted and scaled to integers as in
+/// [`minimize_product`](fn.minimize_product.html). A zero likelihood has no
+/// logarithm, so every entry below `epsilon` is raised to `epsilon` first. Such
+/// entries cost `-ln(epsilon)`, which makes them a last resort rather than
+/// forbidden: they're only assigned when every maximal matching needs one, and
+/// then as few of them as possible. Pick `epsilon` well below any likelihood
+/// that should be told apart from the others.
+///
+/// # Panics
+///
+/// Panics if `epsilon` is not finite and strictly positive, or if any entry is
+/// negative, infinite or NaN.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::maximize_product_f64;
+///
+/// fn main() {
+///     // The sum is maximized by the diagonal (1.0 + 0.1 = 1.1, rather than 0.5 + 0.5 = 1.0)...
+///     let matrix = vec![
+///         1.0, 0.5,
+///         0.5, 0.1,
+///     ];
+///
+///     // ...but the product by the anti-diagonal (0.5 * 0.5 = 0.25, rather than 1.0 * 0.1 = 0.1).
+///     assert_eq!(maximize_product_f64(&matrix, 2, 2, 1e-12), vec![Some(1), Some(0)]);
+///
+///     // Row 1 can't take column 0, so row 0 has to give up column 1
+///     let matrix = vec![
+///         0.5, 0.9,
+///         0.0, 0.8,
+///     ];
+///     assert_eq!(maximize_product_f64(&matrix, 2, 2, 1e-12), vec![Some(0), Some(1)]);
+/// }
+/// ```
+#[must_use]
+pub fn maximize_product_f64(matrix: &[f64], height: usize, width: usize, epsilon: f64) -> Vec<Option<usize>> {
+    assert!(epsilon.is_finite() && epsilon > 0.0, "epsilon {} must be finite and positive", epsilon);
+    let logs = matrix[..height * width].iter()
+        .map(|&likelihood| {
+            assert!(likelihood.is_finite() && likelihood >= 0.0, "likelihood {} must be finite and non-negative", likelihood);
+            -likelihood.max(epsilon).ln()
+        })
+        .collect::<Vec<_>>();
+
+    minimize_logs(&logs, height, width)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for 0/1 costs, where `true` costs
+/// one and `false` costs nothing.
+///
+/// Minimizing the number of `true` entries used is the same as maximizing the
+/// number of `false` ones, so this finds a maximum matching on the `false`
+/// entries, then pairs up the leftover rows and columns arbitrarily. Rows are
+/// matched greedily where possible, and the rest along shortest augmenting paths
+/// found by breadth-first search, so there's no cost workspace or dual update.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_binary;
+///
+/// fn main() {
+///     let matrix = vec![
+///         false, true,  true,
+///         false, true,  true,
+///         true,  false, true,
+///     ];
+///
+///     // Only one of rows 0 and 1 can take column 0 for free
+///     assert_eq!(minimize_binary(&matrix, 3, 3), vec![Some(0), Some(2), Some(1)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_binary(matrix: &[bool], height: usize, width: usize) -> Vec<Option<usize>> {
+    let free = |i: usize, j: usize| !matrix[width * i + j];
+
+    let mut result = vec![None; height];
+    let mut owner = vec![None; width];
+
+    // Greedy pass first, so augmenting paths are only needed for the rest
+    for (i, col) in result.iter_mut().enumerate() {
+        if let Some(j) = (0..width).find(|&j| free(i, j) && owner[j].is_none()) {
+            *col = Some(j);
+            owner[j] = Some(i);
+        }
+    }
+
+    // parent[j] is the row that reached column j in the current search
+    let mut parent = vec![0; width];
+    let mut seen = FixedBitSet::with_capacity(width);
+    let mut queue = Vec::with_capacity(height);
+
+    for start in 0..height {
+        if result[start].is_some() { continue }
+
+        seen.clear();
+        queue.clear();
+        queue.push(start);
+
+        let mut head = 0;
+        let mut end = None;
+        while head < queue.len() && end.is_none() {
+            let i = queue[head];
+            head += 1;
+            for j in (0..width).filter(|&j| free(i, j)) {
+                if on!(seen, j) { continue }
+                seen.insert(j);
+                parent[j] = i;
+                match owner[j] {
+                    Some(k) => queue.push(k),
+                    None => { end = Some(j); break }
+                }
+            }
+        }
+
+        // Flip the path, from the free column back to `start`
+        let mut next = end;
+        while let Some(j) = next {
+            let i = parent[j];
+            next = result[i];
+            result[i] = Some(j);
+            owner[j] = Some(i);
+        }
+    }
+
+    // Every remaining entry costs one, so any pairing of the rest is optimal
+    let mut unused = (0..width).filter(|&j| owner[j].is_none());
+    for j in result.iter_mut().filter(|j| j.is_none()) {
+        match unused.next() {
+            Some(col) => *j = Some(col),
+            None => break,
+        }
+    }
+
+    result
+}
+
+/// Solves the linear bottleneck assignment problem: finds a maximal matching
+/// that minimizes the largest cost among its assigned entries, rather than
+/// their sum.
+///
+/// Returns the assignment, in the format of [`minimize`](fn.minimize.html), and
+/// its bottleneck: the largest original cost over the assigned entries (zero if
+/// nothing can be assigned). Costs are compared but never added, so negative costs
+/// are used as-is rather than clamped.
+///
+/// This binary searches over the distinct costs for the smallest threshold
+/// such that the entries at or below it still admit a matching of
+/// `min(height, width)` rows, found with Kuhn's algorithm. Ties are broken
+/// arbitrarily: the result isn't necessarily optimal for the sum.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_bottleneck};
+///
+/// fn main() {
+///     let matrix = vec![
+///         0,  6,
+///         6, 10,
+///     ];
+///
+///     // The minimum sum is 0 + 10 = 10, with a bottleneck of 10, while
+///     // the minimum bottleneck is 6, with a sum of 6 + 6 = 12
+///     assert_eq!(minimize(&matrix, 2, 2), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize_bottleneck(&matrix, 2, 2), (vec![Some(1), Some(0)], 6));
+/// }
+/// ```
+#[must_use]
+pub fn minimize_bottleneck<N>(matrix: &[N], height: usize, width: usize) -> (Vec<Option<usize>>, N)
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 { return (vec![None; height], N::zero()) }
+
+    let matrix = &matrix[..height * width];
+    let mut values = matrix.to_vec();
+    values.sort();
+    values.dedup();
+
+    let rows = height.min(width);
+    let within = |threshold: N| {
+        let adjacent = (0..height)
+            .map(|i| (0..width).filter(|&j| matrix[width * i + j] <= threshold).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        max_matching(&adjacent, width)
+    };
+
+    // The largest value always admits a complete matching
+    let (mut lo, mut hi) = (0, values.len() - 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if within(values[mid]).iter().filter(|j| j.is_some()).count() == rows {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    let assignment = within(values[lo]);
+    let bottleneck = assignment.iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| matrix[width * i + j]))
+        .max()
+        .unwrap();
+
+    (assignment, bottleneck)
+}
+
+/// Source of costs for [`minimize_source`](fn.minimize_source.html), read on demand.
+///
+/// Implemented for every `Fn(usize, usize) -> N`. Implement it directly to read
+/// costs from somewhere other than a slice, such as a memory-mapped file.
+pub trait CostSource<N> {
+    /// Returns the cost of entry `(i, j)`.
+    fn cost(&self, i: usize, j: usize) -> N;
+}
+
+impl<N, F: Fn(usize, usize) -> N> CostSource<N> for F {
+    fn cost(&self, i: usize, j: usize) -> N {
+        self(i, j)
+    }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that reads costs from a
+/// [`CostSource`](trait.CostSource.html) instead of a slice, so the input
+/// matrix never needs to be held in memory as a `Vec`.
+///
+/// Each cost is read exactly once, in row-major order, and
+/// copied into the workspace. The workspace itself is still dense: solving takes
+/// `height * width * (size_of::<N>() + 2)` bytes for the reduced costs and the
+/// starred and primed zeros, e.g. about 15 GB for a 50,000 x 50,000 matrix of `u32`.
+/// This only saves the memory for the input, so it helps most when the input is
+/// on disk, or computed. For huge matrices where most entries are irrelevant, see
+/// [`minimize_csr`](fn.minimize_csr.html) instead. As in `minimize`, costs are
+/// clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_source, CostSource};
+///
+/// /// Little-endian `u32` costs, as they'd be laid out in a memory-mapped file.
+/// struct Bytes<'a> {
+///     data: &'a [u8],
+///     width: usize,
+/// }
+///
+/// impl<'a> CostSource<u32> for Bytes<'a> {
+///     fn cost(&self, i: usize, j: usize) -> u32 {
+///         let k = 4 * (self.width * i + j);
+///         let mut bytes = [0; 4];
+///         bytes.copy_from_slice(&self.data[k..k + 4]);
+///         u32::from_le_bytes(bytes)
+///     }
+/// }
+///
+/// fn main() {
+///     let data = [1u32, 2, 1, 4, 5, 6, 7, 8, 9]
+///         .iter()
+///         .flat_map(|cost| cost.to_le_bytes().to_vec())
+///         .collect::<Vec<_>>();
+///
+///     let source = Bytes { data: &data, width: 3 };
+///     assert_eq!(minimize_source(&source, 3, 3), vec![Some(2), Some(1), Some(0)]);
+///
+///     // Closures work too
+///     assert_eq!(minimize_source(&|i, j| (i + 1) * (j + 1), 2, 2), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_source<N, S>(source: &S, height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt,
+          S: CostSource<N> + ?Sized,
+{
+    minimize_by(height, width, |i, j| clamp(&source.cost(i, j)))
+}
+
+/// Read-only view of a cost matrix, solved by [`solve_assignment`](fn.solve_assignment.html).
+///
+/// Implemented for:
+///
+/// - `(&[N], usize, usize)`: a 1D slice in row-major order with its height and width,
+///   as taken by [`minimize`](fn.minimize.html).
+/// - `Array2<N>`: a 2D array in any memory layout.
+/// - `[&[N]]`: a slice of rows, which must all have the same length: `dims` panics otherwise.
+///
+/// Implement it to solve directly over other matrix types, such as those of
+/// another linear algebra crate.
+pub trait CostMatrixView {
+    /// Type of each cost.
+    type Cost;
+
+    /// Returns the height and width of the matrix.
+    fn dims(&self) -> (usize, usize);
+
+    /// Returns the cost of entry `(i, j)`.
+    fn get(&self, i: usize, j: usize) -> Self::Cost;
+}
+
+impl<N: Copy> CostMatrixView for (&[N], usize, usize) {
+    type Cost = N;
+
+    fn dims(&self) -> (usize, usize) {
+        (self.1, self.2)
+    }
+
+    fn get(&self, i: usize, j: usize) -> N {
+        self.0[self.2 * i + j]
+    }
+}
+
+impl<N: Copy> CostMatrixView for Array2<N> {
+    type Cost = N;
+
+    fn dims(&self) -> (usize, usize) {
+        self.dim()
+    }
+
+    fn get(&self, i: usize, j: usize) -> N {
+        self[(i, j)]
+    }
+}
+
+impl<N: Copy> CostMatrixView for [&[N]] {
+    type Cost = N;
+
+    fn dims(&self) -> (usize, usize) {
+        let width = self.first().map_or(0, |row| row.len());
+        if let Some(i) = self.iter().position(|row| row.len() != width) {
+            panic!("row {} has {} entries, expected {}", i, self[i].len(), width)
+        }
+        (self.len(), width)
+    }
+
+    fn get(&self, i: usize, j: usize) -> N {
+        self[i][j]
+    }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) generic over the matrix type:
+/// solves any [`CostMatrixView`](trait.CostMatrixView.html). As in `minimize`,
+/// costs are clamped to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+/// extern crate ndarray;
+///
+/// use hungarian::solve_assignment;
+/// use ndarray::arr2;
+///
+/// fn main() {
+///     let expected = vec![Some(2), Some(1), Some(0)];
+///
+///     let matrix = [1, 2, 1, 4, 5, 6, 7, 8, 9];
+///     assert_eq!(solve_assignment(&(&matrix[..], 3, 3)), expected);
+///
+///     let matrix = arr2(&[[1, 2, 1], [4, 5, 6], [7, 8, 9]]);
+///     assert_eq!(solve_assignment(&matrix), expected);
+///
+///     let rows: [&[i32]; 3] = [&[1, 2, 1], &[4, 5, 6], &[7, 8, 9]];
+///     assert_eq!(solve_assignment(&rows[..]), expected);
+/// }
+/// ```
+#[must_use]
+pub fn solve_assignment<V>(view: &V) -> Vec<Option<usize>>
+    where V: CostMatrixView + ?Sized,
+          V::Cost: NumAssign + PrimInt,
+{
+    let (height, width) = view.dims();
+    minimize_by(height, width, |i, j| clamp(&view.get(i, j)))
+}
+
+/// Variant of [`minimize_with_capacities`](fn.minimize_with_capacities.html) where
+/// each column `j` must be assigned to at least `lo[j]` and at most `hi[j]` rows,
+/// while each row is still assigned to at most one column.
+///
+/// As many rows as possible are assigned, i.e. `min(height, sum(hi))`. Internally,
+/// each column is split into `lo[j]` required copies and `hi[j] - lo[j]` optional
+/// ones, and the problem is padded into a square one: dummy rows absorb the optional
+/// copies left over, and may never take a required one, while dummy columns absorb
+/// the rows left over. With `e = sum(min(hi[j], height))` copies, this runs in `O(n^3)`
+/// time and `O(n^2)` space, where `n = height + e - min(height, e)`.
+///
+/// # Errors
+///
+/// Returns an `InfeasibleError` if `lo[j] > hi[j]` for some column, or if there
+/// aren't enough rows to satisfy every lower bound.
+///
+/// # Panics
+///
+/// Panics if `lo` or `hi` does not have exactly `width` entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_bounds;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 9,
+///         2, 9,
+///         3, 9,
+///     ];
+///
+///     // Every row prefers column 0, but column 1 needs a row
+///     assert_eq!(
+///         minimize_with_bounds(&matrix, 3, 2, &[1, 1], &[2, 2]),
+///         Ok(vec![Some(0), Some(0), Some(1)])
+///     );
+///     assert!(minimize_with_bounds(&matrix, 3, 2, &[2, 2], &[2, 2]).is_err());
+/// }
+/// ```
+pub fn minimize_with_bounds<N>(matrix: &[N], height: usize, width: usize, lo: &[usize], hi: &[usize]) -> Result<Vec<Option<usize>>, InfeasibleError>
+    where N: NumAssign + PrimInt
+{
+    assert_eq!(lo.len(), width, "lo must have one entry per column");
+    assert_eq!(hi.len(), width, "hi must have one entry per column");
+
+    if lo.iter().zip(hi).any(|(lo, hi)| lo > hi) || lo.iter().sum::<usize>() > height {
+        return Err(InfeasibleError)
+    }
+
+    // Original column of each copy, and whether it's required
+    let copies = (0..width)
+        .flat_map(|j| (0..hi[j].min(height)).map(move |k| (j, k < lo[j])))
+        .collect::<Vec<_>>();
+
+    let e = copies.len();
+    let r = height.min(e);
+    if r == 0 { return Ok(vec![None; height]) }
+
+    let n = height + e - r;
+    let mut m = Array2::zeros((n, n));
+    let mut mask = Array2::from_elem((n, n), true);
+
+    for (c, &(j, required)) in copies.iter().enumerate() {
+        for i in 0..height {
+            set!(m, i, c, clamp(&matrix[width * i + j]));
+        }
+        if required {
+            for i in height..n { set!(mask, i, c, false) }
+        }
+    }
+
+    for i in height..n {
+        for c in e..n { set!(mask, i, c, false) }
+    }
+
+    let stars = solve(&mut m, Some(&mask))
+        .expect("padded problem always has a perfect matching");
+
+    Ok((0..height)
+        .map(|i| (0..e).find(|&c| get!(stars, i, c)).map(|c| copies[c].0))
+        .collect())
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also returns the original cost
+/// of each assigned entry.
+///
+/// Entry `i` is `Some((j, matrix[i][j]))` if row `i` is assigned to column `j`,
+/// and `None` otherwise. Costs are reported as given, before any clamping.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_detailed;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         3, 5,
+///         4, 6,
+///     ];
+///
+///     assert_eq!(minimize_detailed(&matrix, 3, 2), vec![Some((1, 2)), Some((0, 3)), None]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_detailed<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<(usize, N)>>
+    where N: NumAssign + PrimInt
+{
+    minimize(matrix, height, width)
+        .into_iter()
+        .enumerate()
+        .map(|(i, j)| j.map(|j| (j, matrix[width * i + j])))
+        .collect()
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that also returns each assigned row's
+/// regret: how much more its assigned entry costs than the cheapest entry in its row.
+///
+/// Entry `i` is `Some((j, regret))` if row `i` is assigned to column `j`, and `None`
+/// otherwise. A row with zero regret got its first choice, while a row with a large
+/// regret gave up its cheapest column for the sake of the total. Costs are clamped
+/// to be greater or equal to zero before comparing, as in `minimize`, so regrets are
+/// never negative.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_with_regret;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         3, 9,
+///     ];
+///
+///     // Row 0 gives up column 0 to row 1, which would otherwise pay 9
+///     assert_eq!(minimize_with_regret(&matrix, 2, 2), vec![Some((1, 1)), Some((0, 0))]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_with_regret<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<(usize, N)>>
+    where N: NumAssign + PrimInt
+{
+    minimize(matrix, height, width)
+        .into_iter()
+        .enumerate()
+        .map(|(i, j)| j.map(|j| {
+            let row = &matrix[width * i..width * (i + 1)];
+            let best = row.iter().map(clamp).min().unwrap();
+            (j, clamp(&row[j]) - best)
+        }))
+        .collect()
+}
+
+/// Error returned by [`read_matrix_csv`](fn.read_matrix_csv.html) and
+/// [`write_assignment_csv`](fn.write_assignment_csv.html).
+///
+/// Requires the `csv` feature.
+#[cfg(feature = "csv")]
+#[derive(Debug)]
+pub enum CsvError {
+    /// The underlying reader or writer failed, or the input isn't valid CSV.
+    Csv(csv::Error),
+    /// The entry at this row and column isn't a valid cost.
+    Parse {
+        /// Row of the offending entry
+        row: usize,
+        /// Column of the offending entry
+        col: usize,
+    },
+    /// The rows don't all have the same length.
+    Ragged(RaggedMatrixError),
+}
+
+#[cfg(feature = "csv")]
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+        CsvError::Csv(ref error) => error.fmt(f),
+        CsvError::Parse { row, col } => write!(f, "invalid cost at ({}, {})", row, col),
+        CsvError::Ragged(ref error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Error for CsvError {}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for CsvError {
+    fn from(error: csv::Error) -> Self { CsvError::Csv(error) }
+}
+
+/// Reads a cost matrix from CSV, with one row per record and no header.
+///
+/// Returns the matrix in row-major order, along with its height and width, ready
+/// to pass to [`minimize`](fn.minimize.html). The width is the length of the first
+/// record. Whitespace around each entry is ignored.
+///
+/// Requires the `csv` feature.
+///
+/// # Errors
+///
+/// - `CsvError::Ragged` if a record's length differs from the first's.
+/// - `CsvError::Parse` if an entry can't be parsed as `N`.
+/// - `CsvError::Csv` if reading fails.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, read_matrix_csv};
+///
+/// fn main() {
+///     let input = "1, 2\n3, 5\n4, 6\n";
+///     let (matrix, height, width) = read_matrix_csv::<u32, _>(input.as_bytes()).unwrap();
+///     assert_eq!((height, width), (3, 2));
+///     assert_eq!(minimize(&matrix, height, width), vec![Some(1), Some(0), None]);
+/// }
+/// ```
+#[cfg(feature = "csv")]
+pub fn read_matrix_csv<N, R>(reader: R) -> Result<(Vec<N>, usize, usize), CsvError>
+    where N: std::str::FromStr,
+          R: std::io::Read,
+{
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let mut matrix = Vec::new();
+    let mut height = 0;
+    let mut width = 0;
+
+    for record in reader.records() {
+        let record = record?;
+        if height == 0 {
+            width = record.len();
+        } else if record.len() != width {
+            return Err(CsvError::Ragged(RaggedMatrixError { row: height, expected: width, found: record.len() }))
+        }
+
+        for (col, field) in record.iter().enumerate() {
+            let cost = field.parse().map_err(|_| CsvError::Parse { row: height, col })?;
+            matrix.push(cost);
+        }
+        height += 1;
+    }
+
+    Ok((matrix, height, width))
+}
+
+/// Writes an assignment as CSV, with a `row,column,cost` header and one record
+/// per row of the matrix.
+///
+/// Unassigned rows have empty `column` and `cost` fields. Costs are looked up
+/// in `matrix`, which has `width` columns, as given.
+///
+/// Requires the `csv` feature.
+///
+/// # Errors
+///
+/// Returns `CsvError::Csv` if writing fails.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::write_assignment_csv;
+///
+/// fn main() {
+///     let matrix = vec![1, 2, 3, 5, 4, 6];
+///     let mut out = Vec::new();
+///     write_assignment_csv(&mut out, &[Some(1), Some(0), None], &matrix, 2).unwrap();
+///     assert_eq!(String::from_utf8(out).unwrap(), "row,column,cost\n0,1,2\n1,0,3\n2,,\n");
+/// }
+/// ```
+#[cfg(feature = "csv")]
+pub fn write_assignment_csv<N, W>(writer: W, assignment: &[Option<usize>], matrix: &[N], width: usize) -> Result<(), CsvError>
+    where N: fmt::Display,
+          W: std::io::Write,
+{
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["row", "column", "cost"])?;
+
+    for (i, &j) in assignment.iter().enumerate() {
+        let (col, cost) = match j {
+            Some(j) => (j.to_string(), matrix[width * i + j].to_string()),
+            None => (String::new(), String::new()),
+        };
+        writer.write_record(&[i.to_string(), col, cost])?;
+    }
+
+    writer.flush().map_err(|error| CsvError::Csv(error.into()))?;
+    Ok(())
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that returns the assignment as a
+/// `height * width` mask, with `true` exactly at the assigned entries.
+///
+/// The mask is always in the original orientation, so it can be used directly as
+/// a (partial) permutation matrix, e.g. after converting it into numbers.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+/// extern crate ndarray;
+///
+/// use hungarian::minimize_matrix_mask;
+/// use ndarray::arr2;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2,
+///         3, 5,
+///         4, 6,
+///     ];
+///
+///     assert_eq!(
+///         minimize_matrix_mask(&matrix, 3, 2),
+///         arr2(&[[false, true], [true, false], [false, false]])
+///     );
+/// }
+/// ```
+#[must_use]
+pub fn minimize_matrix_mask<N>(matrix: &[N], height: usize, width: usize) -> Array2<bool>
+    where N: NumAssign + PrimInt
+{
+    let mut mask = Array2::from_elem((height, width), false);
+    for (i, j) in minimize(matrix, height, width).into_iter().enumerate() {
+        if let Some(j) = j { set!(mask, i, j, true) }
+    }
+    mask
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that only assigns the rows listed in
+/// `rows`, against every column. Unlike [`minimize_window`](fn.minimize_window.html),
+/// the rows don't need to be contiguous.
+///
+/// Returns a vector of length `height`, where excluded rows are always `None`,
+/// and each listed row is assigned as it would be in the `rows.len() * width`
+/// problem made of just those rows, in the order listed.
+///
+/// # Panics
+///
+/// Panics if a row in `rows` is out of bounds, or listed more than once.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_row_subset;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 9,
+///         9, 9,
+///         1, 2,
+///         9, 9,
+///     ];
+///
+///     assert_eq!(minimize_row_subset(&matrix, 4, 2, &[0, 2]), vec![Some(0), None, Some(1), None]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_row_subset<N>(matrix: &[N], height: usize, width: usize, rows: &[usize]) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let mut listed = FixedBitSet::with_capacity(height);
+    for &i in rows {
+        assert!(i < height, "row {} out of bounds for height {}", i, height);
+        assert!(off!(listed, i), "row {} listed more than once", i);
+        listed.insert(i);
+    }
+
+    let subset = minimize_by(rows.len(), width, |k, j| clamp(&matrix[width * rows[k] + j]));
+
+    let mut result = vec![None; height];
+    for (&i, j) in rows.iter().zip(subset) {
+        result[i] = j;
+    }
+    result
+}
+
+/// Finds a maximal matching that minimizes the sum of its `k` most expensive
+/// assigned costs, rather than the sum of all of them.
+///
+/// This is exact. For any threshold `t`, the sum of the `k` largest of some costs is
+/// at most `k * t` plus the sum of how far each cost exceeds `t`, with equality when
+/// `t` is the `k`th largest. So the optimum is found by solving one assignment problem
+/// with costs `max(c - t, 0)` for each distinct cost `t`, and keeping the matching whose
+/// `k` largest costs sum the least. With `d` distinct costs, this takes `O(d * n^3)`
+/// time. As in [`minimize`](fn.minimize.html), costs are clamped to be greater or
+/// equal to zero.
+///
+/// With `k = 1` this minimizes the bottleneck, and with `k >= min(height, width)`
+/// it's the same as `minimize`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_top_k_sum};
+///
+/// fn main() {
+///     let matrix = vec![
+///         0,  6,
+///         6, 10,
+///     ];
+///
+///     // The cheapest total (0 + 10) has the most expensive single edge
+///     assert_eq!(minimize(&matrix, 2, 2), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize_top_k_sum(&matrix, 2, 2, 1), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_top_k_sum<N>(matrix: &[N], height: usize, width: usize, k: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    if k >= height.min(width) { return minimize(matrix, height, width) }
+
+    let matrix = matrix[..height * width].iter().map(clamp).collect::<Vec<_>>();
+
+    // Sum of the `k` largest assigned costs
+    let top_k = |assignment: &[Option<usize>]| {
+        let mut costs = assignment.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| matrix[width * i + j]))
+            .collect::<Vec<_>>();
+        costs.sort_by(|a, b| b.cmp(a));
+        costs.into_iter().take(k).fold(N::zero(), |acc, cost| acc + cost)
+    };
+
+    let mut thresholds = matrix.clone();
+    thresholds.sort();
+    thresholds.dedup();
+
+    let mut best = minimize(&matrix, height, width);
+    let mut best_cost = top_k(&best);
+
+    for t in thresholds {
+        let excess = matrix.iter()
+            .map(|&cost| if cost > t { cost - t } else { N::zero() })
+            .collect::<Vec<_>>();
+        let assignment = minimize(&excess, height, width);
+        let cost = top_k(&assignment);
+        if cost < best_cost {
+            best = assignment;
+            best_cost = cost;
+        }
+    }
+
+    best
+}
+
+/// Which side of the cost matrix [`minimize_prefer`](fn.minimize_prefer.html) reports
+/// the assignment for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// One entry per row, holding its assigned column.
+    Rows,
+    /// One entry per column, holding its assigned row.
+    Columns,
+}
+
+/// Computes the same minimum cost assignment as [`minimize`](fn.minimize.html),
+/// reported along the axis given by `prefer`.
+///
+/// A rectangular problem always matches `min(height, width)` pairs, so every row
+/// and column of the shorter side is assigned, and the longer side keeps the rest
+/// as `None`. Which side the caller thinks of as mandatory only changes how the
+/// same matching is read: `Axis::Rows` gives `height` entries, as `minimize` does,
+/// and `Axis::Columns` gives `width` entries, as
+/// [`transpose_assignment`](fn.transpose_assignment.html) would. Costs are clamped
+/// to be greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_prefer, Axis};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 3,
+///         3, 1, 2,
+///     ];
+///
+///     // Both rows are matched, and column 2 is left over
+///     assert_eq!(minimize_prefer(&matrix, 2, 3, Axis::Rows), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize_prefer(&matrix, 2, 3, Axis::Columns), vec![Some(0), Some(1), None]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_prefer<N>(matrix: &[N], height: usize, width: usize, prefer: Axis) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let assignment = minimize(matrix, height, width);
+    match prefer {
+        Axis::Rows => assignment,
+        Axis::Columns => transpose_assignment(&assignment, height, width),
+    }
+}
+
+/// Returns the equality subgraph at the optimum: every `(row, column)` pair whose
+/// reduced cost is zero once [`minimize`](fn.minimize.html)'s problem is solved,
+/// in row-major order.
+///
+/// By complementary slackness, every minimum cost assignment only uses these
+/// edges, so this exposes all of the optimal alternatives at once. For a square
+/// matrix, the converse holds too: every perfect matching of these edges is a
+/// minimum cost assignment. A rectangular matrix is padded with zero cost dummy
+/// rows or columns before solving, which constrain its optima further, so only
+/// the first direction is guaranteed. As in `minimize`, costs are clamped to be
+/// greater or equal to zero.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::equality_subgraph;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 1, 3,
+///         1, 1, 3,
+///         2, 3, 1,
+///     ];
+///
+///     // Rows 0 and 1 can swap columns 0 and 1
+///     assert_eq!(equality_subgraph(&matrix, 3, 3), vec![(0, 0), (0, 1), (1, 0), (1, 1), (2, 2)]);
+/// }
+/// ```
+#[must_use]
+pub fn equality_subgraph<N>(matrix: &[N], height: usize, width: usize) -> Vec<(usize, usize)>
+    where N: NumAssign + PrimInt
+{
+    if height == 0 || width == 0 { return Vec::new() }
+
+    tight_entries(matrix, height, width)
+        .indexed_iter()
+        .filter(|&((i, j), &tight)| tight && i < height && j < width)
+        .map(|((i, j), _)| (i, j))
+        .collect()
+}
+
+/// Finds any complete matching that avoids the entries equal to `forbidden`,
+/// ignoring every other cost, or `None` if there isn't one.
+///
+/// A matching is complete if it covers the shorter side, i.e. assigns
+/// `min(height, width)` rows. This answers "can everything be matched?" with
+/// Kuhn's algorithm, in `O(height * e)` time over the `e` feasible entries, which
+/// is cheaper than [`minimize_with_sentinel`](fn.minimize_with_sentinel.html) when
+/// the cost doesn't matter yet. By Hall's theorem, `None` means some set of rows
+/// (or columns) on the shorter side is feasible with fewer columns (or rows) than
+/// it has members.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::feasible_matching;
+///
+/// fn main() {
+///     let matrix = vec![
+///         5, 0, 0,
+///         1, 0, 2,
+///         0, 0, 3,
+///     ];
+///
+///     // Column 1 is forbidden everywhere, so one of the rows is left over
+///     assert_eq!(feasible_matching(&matrix, 3, 3, 0), None);
+///     assert_eq!(feasible_matching(&matrix[..6], 2, 3, 0), Some(vec![Some(0), Some(2)]));
+/// }
+/// ```
+#[must_use]
+pub fn feasible_matching<N>(matrix: &[N], height: usize, width: usize, forbidden: N) -> Option<Vec<Option<usize>>>
+    where N: NumAssign + PrimInt
+{
+    let adjacent = (0..height)
+        .map(|i| (0..width).filter(|&j| matrix[width * i + j] != forbidden).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let assignment = max_matching(&adjacent, width);
+    let matched = assignment.iter().filter(|j| j.is_some()).count();
+    if matched == height.min(width) { Some(assignment) } else { None }
+}
+
+/// Builds the `height * width` matrix whose entries count up from zero in row-major
+/// order, so entry `(i, j)` is `width * i + j`.
+///
+/// Every row has the same differences between its columns, so every assignment of
+/// a square matrix costs the same, and the solver has to break the tie. This is
+/// the fixture of the `hungarian_NxN` benchmarks, and a reproducible instance for
+/// benchmarking against other solvers.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::sequential_matrix;
+///
+/// fn main() {
+///     assert_eq!(sequential_matrix(2, 3), vec![
+///         0, 1, 2,
+///         3, 4, 5,
+///     ]);
+/// }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+pub fn sequential_matrix(height: usize, width: usize) -> Vec<u64> {
+    (0..height * width).map(|k| k as u64).collect()
+}
+
+/// Builds the `n * n` matrix whose entry `(i, j)` is `(i + 1) * (j + 1)`.
+///
+/// Its unique optimum is the anti-diagonal, but each row reduction leaves a single
+/// zero in the first column, so the solver needs `O(n)` dual updates per row. This
+/// is the fixture of the `hungarian_worst_case_NxN` benchmarks.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, product_matrix};
+///
+/// fn main() {
+///     let matrix = product_matrix(3);
+///     assert_eq!(matrix, vec![
+///         1, 2, 3,
+///         2, 4, 6,
+///         3, 6, 9,
+///     ]);
+///     assert_eq!(minimize(&matrix, 3, 3), vec![Some(2), Some(1), Some(0)]);
+/// }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+pub fn product_matrix(n: usize) -> Vec<u64> {
+    (0..n * n).map(|k| ((k / n + 1) * (k % n + 1)) as u64).collect()
+}
+
+/// Builds an `n * n` matrix of zeros and ones, whose structure lines up with the
+/// index order in which the solver scans rows and columns.
+///
+/// The first `n / 2` rows are settled: row `i` has its only zero in column `i`. The
+/// other rows and columns form a block whose zeros lie above its anti-diagonal, so
+/// the unique optimum costs zero and assigns the block along its anti-diagonal.
+/// Starring the first zero of each row fills only half of the block, and every
+/// search for an uncovered zero then scans past all of the settled rows first. This
+/// is the fixture of the `hungarian_staircase_NxN` benchmarks, where
+/// [`minimize_shuffled`](fn.minimize_shuffled.html) breaks up both.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, staircase_matrix};
+///
+/// fn main() {
+///     let matrix = staircase_matrix(5);
+///     assert_eq!(matrix, vec![
+///         0, 1, 1, 1, 1,
+///         1, 0, 1, 1, 1,
+///         1, 1, 0, 0, 0,
+///         1, 1, 0, 0, 1,
+///         1, 1, 0, 1, 1,
+///     ]);
+///     assert_eq!(minimize(&matrix, 5, 5), vec![Some(0), Some(1), Some(4), Some(3), Some(2)]);
+/// }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+pub fn staircase_matrix(n: usize) -> Vec<u64> {
+    let settled = n / 2;
+    let block = n - settled;
+    (0..n * n)
+        .map(|k| {
+            let (i, j) = (k / n, k % n);
+            let zero = if i < settled {
+                j == i
+            } else {
+                j >= settled && (i - settled) + (j - settled) < block
+            };
+            if zero { 0 } else { 1 }
+        })
+        .collect()
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that finds the assignment whose assigned
+/// costs are as uniform as possible: the one of minimum variance, rather than minimum sum.
+///
+/// This is exact. Every maximal matching assigns `k = min(height, width)` entries,
+/// and the variance of some costs is the least mean squared distance to any target
+/// `t`, reached when `t` is their mean. So the optimum is found by solving one
+/// assignment problem with costs `(c - t)^2` for each achievable mean `t = s / k`,
+/// and keeping the matching with the least squared distance. The sums `s` range over
+/// the integers between the minimum and maximum assignment costs, so with a gap of
+/// `d` between those, this takes `O(d * n^3)` time: it suits costs from a small
+/// range, such as ratings or hours, rather than arbitrary ones. Squared distances
+/// are computed in `i128`. As in `minimize`, costs are clamped to be greater or
+/// equal to zero.
+///
+/// Ties are broken arbitrarily: among assignments of minimum variance, the result
+/// isn't necessarily the cheapest.
+///
+/// # Panics
+///
+/// Panics if the squared distances could overflow `i128`: with `c` the largest
+/// clamped cost, `(k * c)^2 * (height + width)` must fit, which holds whenever
+/// `k * c` is below `2^58` and the matrix has fewer than `2^10` rows and columns in
+/// total. In practice the running time limits `k * c` to a few thousand long before.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize, minimize_variance};
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 4,
+///         4, 6,
+///     ];
+///
+///     // The cheapest total (1 + 6) has the least uniform costs
+///     assert_eq!(minimize(&matrix, 2, 2), vec![Some(0), Some(1)]);
+///     assert_eq!(minimize_variance(&matrix, 2, 2), vec![Some(1), Some(0)]);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_variance<N>(matrix: &[N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    let k = height.min(width) as i128;
+    if k <= 1 { return minimize(matrix, height, width) }
+
+    let costs = matrix[..height * width].iter()
+        .map(|c| clamp(c).to_i128().expect("minimize_variance: cost doesn't fit in i128"))
+        .collect::<Vec<_>>();
+
+    // Every squared distance is at most `(k * max)^2`, and the solver's
+    // intermediate sums add up at most `height + width` of them
+    let max = costs.iter().cloned().max().unwrap();
+    let fits = k.checked_mul(max)
+        .and_then(|reach| reach.checked_mul(reach))
+        .and_then(|square| square.checked_mul((height + width) as i128))
+        .is_some();
+    assert!(fits, "minimize_variance: costs are too large for squared distances in i128");
+
+    // Bounds on the sum of any maximal matching
+    let lo = minimize_cost_only(&costs, height, width);
+    let hi = k * max - minimize_cost_only(&costs.iter().map(|&c| max - c).collect::<Vec<_>>(), height, width);
+
+    // Scaled by `k` so that every target `s / k` is an integer
+    let mut best = (i128::MAX, Vec::new());
+    for s in lo..=hi {
+        let squared = costs.iter().map(|&c| (k * c - s) * (k * c - s)).collect::<Vec<_>>();
+        let assignment = minimize(&squared, height, width);
+        let distance = assignment.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| squared[width * i + j]))
+            .sum::<i128>();
+        if distance < best.0 { best = (distance, assignment) }
+    }
+    best.1
+}
+
+/// Variant of [`minimize`](fn.minimize.html) for branch and bound: returns the
+/// optimal assignment and its cost, or `None` as soon as the optimal cost is
+/// known to exceed `upper_bound`, e.g. the cost of an incumbent solution.
+///
+/// Rows are matched along shortest augmenting paths, one at a time. After `r` of
+/// them, the matching is the cheapest of size `r`, and since costs are non-negative,
+/// no complete matching costs less. So once that running lower bound exceeds
+/// `upper_bound`, the rest of the solve is skipped. A tight bound on an expensive
+/// problem can then return after only a few paths. As in `minimize`, costs are
+/// clamped to be greater or equal to zero, and the returned cost is the sum of the
+/// clamped assigned entries.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_bounded_by;
+///
+/// fn main() {
+///     let matrix = vec![
+///         1, 2, 1,
+///         4, 3, 6,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_bounded_by(&matrix, 3, 3, 20), Some((vec![Some(2), Some(1), Some(0)], 11)));
+///     assert_eq!(minimize_bounded_by(&matrix, 3, 3, 11), Some((vec![Some(2), Some(1), Some(0)], 11)));
+///     assert_eq!(minimize_bounded_by(&matrix, 3, 3, 10), None);
+/// }
+/// ```
+#[must_use]
+pub fn minimize_bounded_by<N>(matrix: &[N], height: usize, width: usize, upper_bound: N) -> Option<(Vec<Option<usize>>, N)>
+    where N: NumAssign + PrimInt
+{
+    let matrix = &matrix[..height * width];
+    let entries = (0..height)
+        .flat_map(|i| (0..width).map(move |j| (i, j, matrix[width * i + j])));
+
+    // Stops before the first path that would take the matching over the bound
+    let assignment = shortest_augmenting_paths(height, width, entries, Some(upper_bound));
+    if assignment.iter().filter(|j| j.is_some()).count() < height.min(width) { return None }
+
+    let cost = assignment.iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| clamp(&matrix[width * i + j])))
+        .fold(N::zero(), |acc, cost| acc + cost);
+
+    if cost > upper_bound { None } else { Some((assignment, cost)) }
+}
+
+/// Variant of [`minimize`](fn.minimize.html) with a hard cap: every entry costing
+/// more than `cap` is forbidden, and every row of the shorter side must still be
+/// assigned.
+///
+/// This differs from [`AssignmentProblem::gate`](struct.AssignmentProblem.html#method.gate),
+/// which forbids the same entries but then prefers to leave rows unassigned, and
+/// returns whatever matching remains under the threshold. Here, a cap that rules out
+/// every complete matching is an error instead. Otherwise, the result is the minimum
+/// cost complete matching among the entries at or below `cap`. As in `minimize`,
+/// costs are clamped to be greater or equal to zero.
+///
+/// # Errors
+///
+/// Returns an `InfeasibleError` if no matching of `min(height, width)` rows avoids
+/// every entry above `cap`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::{minimize_with_max_cost, InfeasibleError};
+///
+/// fn main() {
+///     let matrix = vec![
+///         4, 9,
+///         1, 7,
+///     ];
+///
+///     // The cheapest matching uses the 9, but there's another under the cap
+///     assert_eq!(minimize_with_max_cost(&matrix, 2, 2, 9), Ok(vec![Some(1), Some(0)]));
+///     assert_eq!(minimize_with_max_cost(&matrix, 2, 2, 8), Ok(vec![Some(0), Some(1)]));
+///
+///     // Column 1 costs more than 6 for every row
+///     assert_eq!(minimize_with_max_cost(&matrix, 2, 2, 6), Err(InfeasibleError));
+/// }
+/// ```
+pub fn minimize_with_max_cost<N>(matrix: &[N], height: usize, width: usize, cap: N) -> Result<Vec<Option<usize>>, InfeasibleError>
+    where N: NumAssign + PrimInt
+{
+    let assignment = solve_partial(
+        height,
+        width,
+        |i, j| clamp(&matrix[width * i + j]),
+        |i, j| matrix[width * i + j] <= cap,
+        false,
+    );
+
+    if assignment.iter().filter(|j| j.is_some()).count() < height.min(width) {
+        return Err(InfeasibleError)
+    }
+    Ok(assignment)
+}
+
+/// Variant of [`minimize`](fn.minimize.html) that reduces `matrix` in place, instead
+/// of copying it into a fresh workspace, for one-shot solves on large matrices.
+///
+/// The caller's slice is viewed as a `height * width` matrix, transposed without
+/// copying when `width < height`, and left holding the reduced costs at the optimum.
+/// Only the starred and primed zero masks and some per-line bookkeeping are
+/// allocated. Since the original costs are gone, this can't clamp them on the fly
+/// as `minimize` does: negative entries are solved with their actual values instead,
+/// so clamp them beforehand to get the same result as `minimize`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate hungarian;
+///
+/// use hungarian::minimize_in_place;
+///
+/// fn main() {
+///     let mut matrix = vec![
+///         1, 2, 1,
+///         4, 3, 6,
+///         7, 8, 9,
+///     ];
+///
+///     assert_eq!(minimize_in_place(&mut matrix, 3, 3), vec![Some(2), Some(1), Some(0)]);
+///
+///     // Every assigned entry is now zero
+///     assert_eq!((matrix[2], matrix[4], matrix[6]), (0, 0, 0));
+/// }
+/// ```
+#[must_use]
+pub fn minimize_in_place<N>(matrix: &mut [N], height: usize, width: usize) -> Vec<Option<usize>>
+    where N: NumAssign + PrimInt
+{
+    // No possible assignment
+    if height == 0 || width == 0 { return vec![None; height] }
+
+    let transposed = width < height;
+    let m = ArrayViewMut2::from_shape((height, width), &mut matrix[..height * width]).unwrap();
+    let mut m = if transposed { m.reversed_axes() } else { m };
+    let (h, w) = m.dim();
+
+    let mut stars = Array2::from_elem((h, w), false);
+    let mut primes = Array2::from_elem((h, w), false);
+    let mut row_cover = FixedBitSet::with_capacity(h);
+    let mut col_cover = FixedBitSet::with_capacity(w);
+    let mut columns = Vec::with_capacity(w);
+
+    // Without forbidden entries, every row can always be matched
+    solve_in(&mut m, None, &mut stars, &mut primes, &mut row_cover, &mut col_cover, &mut columns, &mut unobserved);
+    assignment(&stars, transposed)
+}
+
+#[cfg(test)]
+mod tests {
+
+    /// Internal macro for converting from a 2D index to a 1D index.
+    macro_rules! index {
+        ($w:expr, $i:expr, $j:expr) => (($w*$i) + $j)
+    }
+
+    use {
+        all_optima,
+        analyze,
+        assignment_edges,
+        canonicalize,
+        check_stars,
+        equality_subgraph,
+        feasible_matching,
+        format_assignment,
+        is_optimal,
+        is_unique_optimum,
+        lower_bound,
+        max_matching,
+        max_safe_cost,
+        max_weight_matching,
+        maximize,
+        maximize_cardinality_under_budget,
+        maximize_floored,
+        maximize_product_f64,
+        mem,
+        minimize,
+        minimize_aggregate,
+        minimize_as,
+        minimize_binary,
+        minimize_blocks,
+        minimize_bottleneck,
+        minimize_bounded_by,
+        minimize_cost_only,
+        minimize_csr,
+        minimize_dedup_columns,
+        minimize_detailed,
+        minimize_expected,
+        minimize_expected_weighted,
+        minimize_explained,
+        minimize_feasible,
+        minimize_fixed,
+        minimize_hinted,
+        minimize_in_place,
+        minimize_iter,
+        minimize_k_best,
+        minimize_matrix_mask,
+        minimize_min_churn,
+        minimize_nested,
+        minimize_optional,
+        minimize_ordered,
+        minimize_owned,
+        minimize_permutation,
+        minimize_points,
+        minimize_prefer,
+        minimize_product,
+        minimize_required,
+        minimize_row_subset,
+        minimize_scaled_f64,
+        minimize_shuffled,
+        minimize_sorted,
+        minimize_source,
+        minimize_then_maximize,
+        minimize_top_k_sum,
+        minimize_u32,
+        minimize_usize,
+        minimize_utilization,
+        minimize_variance,
+        minimize_window,
+        minimize_with_bounds,
+        minimize_with_capacities,
+        minimize_with_column_groups,
+        minimize_with_max_cost,
+        minimize_with_potentials,
+        minimize_with_regret,
+        minimize_with_sentinel,
+        minimize_with_slack,
+        minimize_with_stars,
+        reduced_cost_matrix,
+        solve_assignment,
+        splitmix64,
+        total_cost,
+        transpose_assignment,
+        try_max_weight_matching,
+        try_maximize,
+        try_minimize,
+        try_minimize_expected,
+        try_minimize_expected_weighted,
+        try_minimize_nonneg,
+        try_minimize_then_maximize,
+        verify_potentials,
+        Array2,
+        AssignmentProblem,
+        Axis,
+        CostSource,
+        DimensionError,
+        ITERATIONS,
+        InfeasibleError,
+        MatrixStats,
+        NegativeCostError,
+        OnlineSolver,
+        OverflowError,
+        Permutation,
+        RaggedMatrixError,
+        Reason,
+        RowAssignment,
+        SolveError,
+        Solver,
+        UnusedColumn,
+        UtilizationReport,
+    };
+
+    use ndarray::arr2;
+    use ordered_float::OrderedFloat;
+
+    #[test]
+    fn test_output_length() {
+        let mut state = 0x1e6;
+        for &(height, width) in &[(1, 1), (1, 7), (7, 1), (3, 8), (8, 3), (5, 5)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let result = minimize(&matrix, height, width);
+            assert_eq!(result.len(), height);
+            assert_eq!(result.iter().filter(|j| j.is_some()).count(), height.min(width));
+        }
+    }
+
+    #[test]
+    fn test_zero_rows_or_columns() {
+        let empty: Vec<i64> = Vec::new();
+        assert_eq!(minimize(&empty, 0, 4), vec![]);
+        assert_eq!(minimize(&empty, 3, 0), vec![None; 3]);
+        assert_eq!(minimize_owned(empty.clone(), 3, 0), vec![None; 3]);
+        assert_eq!(Solver::new().minimize(&empty, 3, 0), vec![None; 3]);
+        assert_eq!(minimize_shuffled(&empty, 3, 0, 0), vec![None; 3]);
+        assert_eq!(minimize_with_stars(&empty, 3, 0), (vec![None; 3], vec![]));
+        assert_eq!(maximize(&empty, 3, 0), vec![None; 3]);
+        assert_eq!(minimize_feasible(&empty, 3, 0), vec![None; 3]);
+        assert_eq!(try_minimize(&empty, 3, 0), Ok(vec![None; 3]));
+        assert_eq!(minimize_window(&[1, 2, 3, 4], 2, 0..2, 1..1), vec![None; 2]);
+    }
+
+    #[test]
+    fn test_basic_0x0() {
+        let matrix: Vec<i32> = Vec::new();
+        assert_eq!(
+            minimize(&matrix, 0, 0),
+            Vec::new()
+        )
+    }
+
+    #[test]
+    fn test_basic_1x1() {
+        let matrix = vec![1];
+        assert_eq!(
+            minimize(&matrix, 1, 1),
+            vec![Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_basic_1x2() {
+        let matrix = vec![
+            1, 2
+        ];
+        assert_eq!(
+            minimize(&matrix, 1, 2),
+            vec![Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_basic_2x1() {
+        let matrix = vec![
+            1,
+            2
+        ];
+        assert_eq!(
+            minimize(&matrix, 2, 1),
+            vec![Some(0), None]
+        );
+    }
+
+    #[test]
+    fn test_basic_2x2() {
+        let matrix = vec![
+            1, 2,
+            2, 1,
+        ];
+        assert_eq!(
+            minimize(&matrix, 2, 2),
+            vec![Some(0), Some(1)]
+        );
     }
 
     // From http://www.math.harvard.edu/archive/20_spring_05/handouts/assignment_overheads.pdf
     #[test]
-    fn test_sales_3x3() {
+    fn test_sales_3x3() {
+        let matrix = vec![
+            250, 400, 350,
+            400, 600, 350,
+            200, 400, 250,
+        ];
+        assert_eq!(
+            minimize(&matrix, 3, 3),
+            vec![Some(1), Some(2), Some(0)]
+        );
+    }
+
+    // From https://brilliant.org/wiki/hungarian-matching/
+    #[test]
+    fn test_party_3x3() {
+        let matrix = vec![
+            108, 125, 150,
+            150, 135, 175,
+            122, 148, 250,
+        ];
+        assert_eq!(
+            minimize(&matrix, 3, 3),
+            vec![Some(2), Some(1), Some(0)]
+        );
+    }
+
+    // From https://en.wikipedia.org/wiki/Hungarian_algorithm#Matrix_interpretation
+    #[test]
+    fn test_wikipedia_4x4() {
+        let matrix = vec![
+            0, 1, 2, 3,
+            4, 5, 6, 0,
+            0, 2, 4, 5,
+            3, 0, 0, 9,
+        ];
+        assert_eq!(
+            minimize(&matrix, 4, 4),
+            vec![Some(1), Some(3), Some(0), Some(2)]
+        );
+    }
+
+    // From https://www.wikihow.com/Use-the-Hungarian-Algorithm
+    #[test]
+    fn test_wikihow_5x5() {
+        let matrix = vec![
+            10, 19, 8, 15, 19,
+            10, 18, 7, 17, 19,
+            13, 16, 9, 14, 19,
+            12, 19, 8, 18, 19,
+            14, 17, 10, 19, 19
+        ];
+        assert_eq!(
+            minimize(&matrix, 5, 5),
+            vec![Some(0), Some(2), Some(3), Some(4), Some(1)]
+        );
+    }
+
+    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
+    #[test]
+    fn test_python_5x5() {
+        let matrix = vec![
+            12,  9, 27, 10, 23,
+             7, 13, 13, 30, 19,
+            25, 18, 26, 11, 26,
+             9, 28, 26, 23, 13,
+            16, 16, 24,  6,  9,
+        ];
+        assert_eq!(
+            51,
+            minimize(&matrix, 5, 5)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
+    #[test]
+    fn test_python_10x10() {
+        let matrix = vec![
+            37, 34, 29, 26, 19,  8,  9, 23, 19, 29,
+             9, 28, 20,  8, 18, 20, 14, 33, 23, 14,
+            15, 26, 12, 28,  6, 17,  9, 13, 21,  7,
+             2,  8, 38, 36, 39,  5, 36,  2, 38, 27,
+            30,  3, 33, 16, 21, 39,  7, 23, 28, 36,
+             7,  5, 19, 22, 36, 36, 24, 19, 30,  2,
+            34, 20, 13, 36, 12, 33,  9, 10, 23,  5,
+             7, 37, 22, 39, 33, 39, 10,  3, 13, 26,
+            21, 25, 23, 39, 31, 37, 32, 33, 38,  1,
+            17, 34, 40, 10, 29, 37, 40,  3, 25,  3,
+        ];
+        assert_eq!(
+            66,
+            minimize(&matrix, 10, 10)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(10, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
+    #[test]
+    fn test_python_20x20() {
+        let matrix = vec![
+            5,  4,  3,  9,  8,  9,  3,  5,  6,  9,  4, 10,  3,  5,  6,  6,  1,  8, 10,  2,
+            10, 9,  9,  2,  8,  3,  9,  9, 10,  1,  7, 10,  8,  4,  2,  1,  4,  8,  4,  8,
+            10, 4,  4,  3,  1,  3,  5, 10,  6,  8,  6,  8,  4, 10,  7,  2,  4,  5,  1,  8,
+            2,  1,  4,  2,  3,  9,  3,  4,  7,  3,  4,  1,  3,  2,  9,  8,  6,  5,  7,  8,
+            3,  4,  4,  1,  4, 10,  1,  2,  6,  4,  5, 10,  2,  2,  3,  9, 10,  9,  9, 10,
+            1, 10,  1,  8,  1,  3,  1,  7,  1,  1,  2,  1,  2,  6,  3,  3,  4,  4,  8,  6,
+            1,  8,  7, 10, 10,  3,  4,  6,  1,  6,  6,  4,  9,  6,  9,  6,  4,  5,  4,  7,
+            8, 10,  3,  9,  4,  9,  3,  3,  4,  6,  4,  2,  6,  7,  7,  4,  4,  3,  4,  7,
+            1,  3,  8,  2,  6,  9,  2,  7,  4,  8, 10,  8, 10,  5,  1,  3, 10, 10,  2,  9,
+            2,  4,  1,  9,  2,  9,  7,  8,  2,  1,  4, 10,  5,  2,  7,  6,  5,  7,  2,  6,
+            4,  5,  1,  4,  2,  3,  3,  4,  1,  8,  8,  2,  6,  9,  5,  9,  6,  3,  9,  3,
+            3,  1,  1,  8,  6,  8,  8,  7,  9,  3,  2,  1,  8,  2,  4,  7,  3,  1,  2,  4,
+            5,  9,  8,  6, 10,  4, 10,  3,  4, 10, 10, 10,  1,  7,  8,  8,  7,  7,  8,  8,
+            1,  4,  6,  1,  6,  1,  2, 10,  5, 10,  2,  6,  2,  4,  5,  5,  3,  5,  1,  5,
+            5,  6,  9, 10,  6,  6, 10,  6,  4,  1,  5,  3,  9,  5,  2, 10,  9,  9,  5,  1,
+            10, 9,  4,  6,  9,  5,  3,  7, 10,  1,  6,  8,  1,  1, 10,  9,  5,  7,  7,  5,
+            2,  6,  6,  6,  6,  2,  9,  4,  7,  5,  3,  2, 10,  3,  4,  5, 10,  9,  1,  7,
+            5,  2,  4,  9,  8,  4,  8,  2,  4,  1,  3,  7,  6,  8,  1,  6,  8,  8, 10, 10,
+            9,  6,  3,  1,  8,  5,  7,  8,  7,  2,  1,  8,  2,  8,  3,  7,  4,  8,  7,  7,
+            8,  4,  4,  9,  7, 10,  6,  2,  1,  5,  8,  5,  1,  1,  1,  9,  1,  3,  5,  3,
+        ];
+        assert_eq!(
+            22,
+            minimize(&matrix, 20, 20)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(20, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    // From https://stackoverflow.com/questions/37687045/hungarian-algorithm-dead-end
+    #[test]
+    fn test_stack_overflow_4x4_zeros() {
+        let matrix = vec![
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 1, 2,
+            0, 0, 3, 4,
+        ];
+        assert_eq!(
+            minimize(&matrix, 4, 4),
+            vec![Some(3), Some(2), Some(1), Some(0)]
+        );
+    }
+
+    // From https://stackoverflow.com/questions/46803600/hungarian-algorithm-wikipedia-method-doesnt-work-for-this-example
+    #[test]
+    fn test_stack_overflow_4x4() {
+        let matrix = vec![
+            35,  0,  0,  0,
+            0 , 30,  0,  5,
+            55,  5,  0, 10,
+            0 , 45, 30, 45,
+        ];
+        assert_eq!(
+            5,
+            minimize(&matrix, 4, 4)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
+                .sum::<u64>()
+        );
+        assert_eq!(
+            minimize(&matrix, 4, 4),
+            vec![Some(1), Some(3), Some(2), Some(0)]
+        );
+    }
+
+    // From https://stackoverflow.com/questions/17419595/hungarian-kuhn-munkres-algorithm-oddity
+    #[test]
+    fn test_stack_overflow_5x5() {
+        let matrix = vec![
+            0, 7, 0, 0, 0,
+            0, 8, 0, 0, 6,
+            5, 0, 7, 3, 4,
+            5, 0, 5, 9, 3,
+            0, 4, 0, 0, 9,
+        ];
+        assert_eq!(
+            3,
+            minimize(&matrix, 5, 5)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
+                .sum::<u64>()
+        );
+        assert_eq!(
+            minimize(&matrix, 5, 5),
+            vec![Some(4), Some(2), Some(3), Some(1), Some(0)]
+        );
+    }
+
+    // From https://stackoverflow.com/questions/37687045/hungarian-algorithm-dead-end
+    #[test]
+    fn test_stack_overflow_6x6() {
+        let matrix = vec![
+            2, 1, 0, 0, 0, 3,
+            2, 0, 4, 5, 2, 7,
+            0, 7, 0, 0, 0, 5,
+            3, 2, 3, 1, 2, 0,
+            0, 0, 6, 3, 3, 5,
+            3, 4, 5, 2, 0, 3,
+        ];
+        assert_eq!(
+            minimize(&matrix, 6, 6),
+            vec![Some(3), Some(1), Some(2), Some(5), Some(0), Some(4)]
+        );
+    }
+
+    // From https://stackoverflow.com/questions/26893961/cannot-solve-hungarian-algorithm
+    #[test]
+    fn test_stack_overflow_14x11() {
+        let matrix = vec![
+             0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+            53, 207, 256, 207, 231, 348, 348, 348, 231, 244, 244,
+           240,  33,  67,  33,  56, 133, 133, 133,  56,  33,  33,
+           460, 107, 200, 107, 122, 324, 324, 324, 122,  33,  33,
+           167, 340, 396, 340, 422, 567, 567, 567, 422, 442, 442,
+           167, 367, 307, 367, 433, 336, 336, 336, 433, 158, 158,
+           160,  20,  37,  20,  31,  70,  70,  70,  31,  22,  22,
+           200, 307, 393, 307, 222, 364, 364, 364, 222, 286, 286,
+           33 , 153, 152, 153, 228, 252, 252, 252, 228,  78,  78,
+           93 , 140, 185, 140,  58, 118, 118, 118,  58,  44,  44,
+           0  ,   7,  22,   7,  19,  58,  58,  58,  19,   0,   0,
+           67 , 153, 241, 153, 128, 297, 297, 297, 128,  39,  39,
+           73 , 253, 389, 253, 253, 539, 539, 539, 253,  36,  36,
+           173, 267, 270, 267, 322, 352, 352, 352, 322, 231, 231,
+        ];
+        assert_eq!(
+            828,
+            minimize(&matrix, 14, 11)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(11, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    // From https://github.com/bmc/munkres/blob/master/munkres.py
+    #[test]
+    fn test_rectangle_3x4() {
+        let matrix = vec![
+            400, 150, 400, 1,
+            400, 450, 600, 2,
+            300, 225, 300, 3,
+        ];
+        assert_eq!(
+            452,
+            minimize(&matrix, 3, 4)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
+                .sum::<u64>()
+        );
+        assert_eq!(
+            minimize(&matrix, 3, 4),
+            vec![Some(1), Some(3), Some(0)]
+        );
+    }
+
+    // Modified from http://www.hungarianalgorithm.com/examplehungarianalgorithm.php
+    #[test]
+    fn test_rectangle_4x5() {
+        let matrix = vec![
+            82, 83, 69, 92, 100,
+            77, 37, 49, 92, 195,
+            11, 69,  5, 86,  93,
+             8,  9, 98, 23, 106,
+        ];
+        assert_eq!(
+            140,
+            minimize(&matrix, 4, 5)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
+                .sum::<u64>()
+        );
+        assert_eq!(
+            minimize(&matrix, 4, 5),
+            vec![Some(2), Some(1), Some(0), Some(3)]
+        );
+    }
+
+    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
+    #[test]
+    fn test_rectangle_5x4() {
+        let matrix = vec![
+            34, 26, 17, 12,
+            43, 43, 36, 10,
+            97, 47, 66, 34,
+            52, 42, 19, 36,
+            15, 93, 55, 80
+        ];
+        assert_eq!(
+            70,
+            minimize(&matrix, 5, 4)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
+                .sum::<u64>()
+        );
+        assert_eq!(
+            minimize(&matrix, 5, 4),
+            vec![Some(1), Some(3), None, Some(2), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_rectangle_nx1() {
+        let max = 100;
+        for n in 1..max {
+            let matrix = (0..n as u64).rev().collect::<Vec<_>>();
+            let mut expected = vec![None; n];
+            expected[n - 1] = Some(0);
+            assert_eq!(minimize(&matrix, n, 1), expected);
+        }
+    }
+
+    #[test]
+    fn test_rectangle_1xn() {
+        let max = 100;
+        for n in 1..max {
+            let matrix = (0..n as u64).rev().collect::<Vec<_>>();
+            let expected = vec![Some(n - 1)];
+            assert_eq!(minimize(&matrix, 1, n), expected);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_shapes_linear() {
+        // A full workspace for these would take tens of gigabytes, so
+        // finishing at all means the single row and column paths are taken
+        let n = 100_000;
+        let matrix = (0..n as u64).map(|k| (k * 7919) % 100_003).collect::<Vec<_>>();
+        let cheapest = (0..n).min_by_key(|&k| matrix[k]).unwrap();
+
+        assert_eq!(minimize(&matrix, 1, n), vec![Some(cheapest)]);
+        assert_eq!(Solver::new().minimize(&matrix, 1, n), vec![Some(cheapest)]);
+
+        let mut expected = vec![None; n];
+        expected[cheapest] = Some(0);
+        assert_eq!(minimize(&matrix, n, 1), expected);
+        assert_eq!(Solver::new().minimize(&matrix, n, 1), expected);
+    }
+
+    #[test]
+    fn test_stress() {
+        for max in 1..100 {
+            let matrix = ::sequential_matrix(max, max);
+            let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
+            assert_eq!(minimize(&matrix, max, max), expected);
+        }
+    }
+
+    #[test]
+    fn test_worst_case() {
+        for max in 1..50 {
+            let matrix = ::product_matrix(max);
+            let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
+            assert_eq!(minimize(&matrix, max, max), expected);
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        let max = 1000;
+        let matrix = ::sequential_matrix(max, max);
+        let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
+        assert_eq!(minimize(&matrix, max, max), expected);
+    }
+
+    #[test]
+    fn test_nested_square() {
+        let matrix = vec![
+            vec![250, 400, 350],
+            vec![400, 600, 350],
+            vec![200, 400, 250],
+        ];
+        assert_eq!(
+            minimize_nested(&matrix),
+            Ok(vec![Some(1), Some(2), Some(0)])
+        );
+    }
+
+    #[test]
+    fn test_nested_slices() {
+        let rows: [&[u32]; 3] = [
+            &[5, 5],
+            &[1, 0],
+            &[2, 3],
+        ];
+        assert_eq!(
+            minimize_nested(&rows),
+            Ok(vec![None, Some(1), Some(0)])
+        );
+    }
+
+    #[test]
+    fn test_nested_ragged() {
+        let matrix = vec![
+            vec![1, 2, 3, 4],
+            vec![2, 3, 4, 1],
+            vec![3, 4, 1, 2],
+            vec![4, 1, 2],
+            vec![1, 2, 3, 4],
+        ];
+        assert_eq!(
+            minimize_nested(&matrix),
+            Err(RaggedMatrixError { row: 3, expected: 4, found: 3 })
+        );
+    }
+
+    #[test]
+    fn test_nested_empty() {
+        let matrix: Vec<Vec<u32>> = Vec::new();
+        assert_eq!(minimize_nested(&matrix), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_required_forces_costlier_solution() {
+        let matrix = vec![
+            1,  2,
+            2,  1,
+            9, 10,
+        ];
+        assert_eq!(
+            minimize(&matrix, 3, 2),
+            vec![Some(0), Some(1), None]
+        );
+        assert_eq!(
+            minimize_required(&matrix, 3, 2, &[2]),
+            Ok(vec![None, Some(1), Some(0)])
+        );
+    }
+
+    #[test]
+    fn test_required_5x4() {
+        let matrix = vec![
+            34, 26, 17, 12,
+            43, 43, 36, 10,
+            97, 47, 66, 34,
+            52, 42, 19, 36,
+            15, 93, 55, 80
+        ];
+        let assignment = minimize_required(&matrix, 5, 4, &[2, 4]).unwrap();
+        assert_eq!(assignment.iter().filter(|a| a.is_none()).count(), 1);
+        assert!(assignment[2].is_some());
+        assert!(assignment[4].is_some());
+        assert_eq!(
+            89,
+            assignment
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_required_square() {
+        let matrix = vec![
+            1, 2,
+            2, 1,
+        ];
+        assert_eq!(
+            minimize_required(&matrix, 2, 2, &[0, 1]),
+            Ok(minimize(&matrix, 2, 2))
+        );
+    }
+
+    #[test]
+    fn test_required_infeasible() {
+        let matrix = vec![
+            1,
+            2,
+            3,
+        ];
+        assert_eq!(
+            minimize_required(&matrix, 3, 1, &[0, 2]),
+            Err(InfeasibleError)
+        );
+    }
+
+    #[test]
+    fn test_format_square() {
+        let matrix = vec![
+            250, 400, 350,
+            400, 600, 350,
+            200, 400, 250,
+        ];
+        let assignment = minimize(&matrix, 3, 3);
+        assert_eq!(
+            format_assignment(&matrix, 3, 3, &assignment),
+            concat!(
+                " 250  [400]  350\n",
+                " 400   600  [350]\n",
+                "[200]  400   250\n",
+                "cost: 950\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_empty() {
+        let matrix: Vec<u32> = Vec::new();
+        assert_eq!(format_assignment(&matrix, 0, 0, &[]), "cost: 0\n");
+    }
+
+    #[test]
+    fn test_maximize_rectangle_3x4() {
+        let matrix = vec![
+            400, 150, 400, 1,
+            400, 450, 600, 2,
+            300, 225, 300, 3,
+        ];
+        assert_eq!(
+            1225,
+            maximize(&matrix, 3, 4)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_maximize_negative() {
+        let matrix = vec![
+            -1, -2,
+            -2, -9,
+        ];
+        assert_eq!(
+            maximize(&matrix, 2, 2),
+            vec![Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_maximize_near_u32_max() {
+        // Transforming via `N::max_value() - v + 1` or a signed
+        // negation would overflow or truncate these costs.
+        let max = u32::MAX;
+        let matrix = vec![
+            max,     max - 1, 0,
+            max - 1, 0,       max,
+            0,       max,     max - 2,
+        ];
+        assert_eq!(
+            try_maximize(&matrix, 3, 3),
+            Ok(vec![Some(0), Some(2), Some(1)])
+        );
+    }
+
+    #[test]
+    fn test_maximize_overflow() {
+        let matrix = vec![
+            i32::MAX, 0,
+            1, i32::MIN,
+        ];
+        assert_eq!(
+            try_maximize(&matrix, 2, 2),
+            Err(OverflowError { row: 1, col: 1 })
+        );
+    }
+
+    #[test]
+    fn test_greedy_optimal() {
+        // Every row reduces to a distinct zero in Step 1,
+        // so Step 2 alone produces the optimal matching.
+        for max in 1..50 {
+            let mut matrix = vec![1; max * max];
+            for i in 0..max {
+                matrix[index!(max, i, max - 1 - i)] = 0;
+            }
+            let expected = (0..max).map(|i| Some(max - 1 - i)).collect::<Vec<_>>();
+            assert_eq!(minimize(&matrix, max, max), expected);
+        }
+    }
+
+    #[test]
+    fn test_capacities_basic() {
+        let matrix = vec![
+            1, 5,
+            2, 6,
+            3, 4,
+        ];
+        assert_eq!(
+            minimize_with_capacities(&matrix, 3, 2, &[2, 1]),
+            vec![Some(0), Some(0), Some(1)]
+        );
+        assert_eq!(
+            minimize_with_capacities(&matrix, 3, 2, &[3, 0]),
+            vec![Some(0), Some(0), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_capacities_unit() {
+        let matrix = vec![
+            34, 26, 17, 12,
+            43, 43, 36, 10,
+            97, 47, 66, 34,
+            52, 42, 19, 36,
+            15, 93, 55, 80
+        ];
+        assert_eq!(
+            minimize_with_capacities(&matrix, 5, 4, &[1, 1, 1, 1]),
+            minimize(&matrix, 5, 4)
+        );
+    }
+
+    #[test]
+    fn test_capacities_short() {
+        let matrix = vec![
+            4, 1, 3,
+            2, 0, 5,
+            3, 2, 2,
+        ];
+        // Only two slots in total: rows 0 and 1 compete for column 1
+        assert_eq!(
+            minimize_with_capacities(&matrix, 3, 3, &[0, 1, 1]),
+            vec![None, Some(1), Some(2)]
+        );
+        assert_eq!(
+            minimize_with_capacities(&matrix, 3, 3, &[0, 0, 0]),
+            vec![None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_edges_rectangle_5x4() {
+        let matrix = vec![
+            34, 26, 17, 12,
+            43, 43, 36, 10,
+            97, 47, 66, 34,
+            52, 42, 19, 36,
+            15, 93, 55, 80
+        ];
+        let assignment = minimize(&matrix, 5, 4);
+        assert_eq!(
+            assignment_edges(&matrix, 5, 4, &assignment),
+            vec![(0, 1, 26), (1, 3, 10), (3, 2, 19), (4, 0, 15)]
+        );
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn test_graph_rectangle_3x4() {
+        let matrix = vec![
+            400, 150, 400, 1,
+            400, 450, 600, 2,
+            300, 225, 300, 3,
+        ];
+        let assignment = minimize(&matrix, 3, 4);
+        let graph = ::assignment_graph(&matrix, 3, 4, &assignment);
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.raw_edges().iter().map(|e| e.weight).sum::<u64>(), 452);
+    }
+
+    #[test]
+    fn test_try_empty() {
+        let matrix: Vec<u32> = Vec::new();
+        assert_eq!(try_minimize(&matrix, 0, 0), Ok(Vec::new()));
+        assert_eq!(try_minimize(&matrix, 0, 3), Ok(Vec::new()));
+        assert_eq!(try_minimize(&matrix, 3, 0), Ok(vec![None; 3]));
+    }
+
+    #[test]
+    fn test_try_valid() {
+        let matrix = vec![
+            400, 150, 400, 1,
+            400, 450, 600, 2,
+            300, 225, 300, 3,
+        ];
+        assert_eq!(
+            try_minimize(&matrix, 3, 4),
+            Ok(vec![Some(1), Some(3), Some(0)])
+        );
+    }
+
+    #[test]
+    fn test_try_length_mismatch() {
+        let matrix = vec![
+            1, 2, 3,
+            4, 5,
+        ];
+        assert_eq!(
+            try_minimize(&matrix, 2, 3),
+            Err(DimensionError::LengthMismatch { expected: 6, found: 5 })
+        );
+    }
+
+    #[test]
+    fn test_try_overflow() {
+        // Overflows on every target
+        assert_eq!(
+            try_minimize::<i32>(&[], usize::MAX / 2 + 1, 2),
+            Err(DimensionError::Overflow { height: usize::MAX / 2 + 1, width: 2 })
+        );
+
+        // 2^16 * 2^16 only overflows a 32-bit usize
+        let result = try_minimize::<i32>(&[], 1 << 16, 1 << 16);
+        #[cfg(target_pointer_width = "32")]
+        assert_eq!(result, Err(DimensionError::Overflow { height: 1 << 16, width: 1 << 16 }));
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(result, Err(DimensionError::LengthMismatch { expected: 1 << 32, found: 0 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix overflows usize")]
+    fn test_minimize_overflow() {
+        let _ = minimize::<i32>(&[], usize::MAX / 2 + 1, 2);
+    }
+
+    #[test]
+    fn test_try_zero_dimension() {
+        let matrix = vec![1, 2, 3];
+        assert_eq!(
+            try_minimize(&matrix, 3, 0),
+            Err(DimensionError::ZeroDimension { height: 3, width: 0, found: 3 })
+        );
+    }
+
+    #[test]
+    fn test_window_matches_copy() {
+        let matrix = vec![
+            37, 34, 29, 26, 19,  8,  9, 23, 19, 29,
+             9, 28, 20,  8, 18, 20, 14, 33, 23, 14,
+            15, 26, 12, 28,  6, 17,  9, 13, 21,  7,
+             2,  8, 38, 36, 39,  5, 36,  2, 38, 27,
+            30,  3, 33, 16, 21, 39,  7, 23, 28, 36,
+             7,  5, 19, 22, 36, 36, 24, 19, 30,  2,
+            34, 20, 13, 36, 12, 33,  9, 10, 23,  5,
+             7, 37, 22, 39, 33, 39, 10,  3, 13, 26,
+            21, 25, 23, 39, 31, 37, 32, 33, 38,  1,
+            17, 34, 40, 10, 29, 37, 40,  3, 25,  3,
+        ];
+        for (rows, cols) in [(2..7, 3..6), (0..3, 0..10), (4..10, 5..9), (1..6, 1..6)].iter().cloned() {
+            let window = rows.clone()
+                .flat_map(|i| cols.clone().map(move |j| (i, j)))
+                .map(|(i, j)| matrix[index!(10, i, j)])
+                .collect::<Vec<_>>();
+            let expected = minimize(&window, rows.len(), cols.len())
+                .into_iter()
+                .map(|j| j.map(|j| j + cols.start))
+                .collect::<Vec<_>>();
+            assert_eq!(minimize_window(&matrix, 10, rows, cols), expected);
+        }
+    }
+
+    #[test]
+    fn test_window_empty() {
+        let matrix = vec![1, 2, 3, 4];
+        assert_eq!(minimize_window(&matrix, 2, 1..1, 0..2), Vec::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_window_overflow() {
+        let matrix = vec![1, 2, 3, 4];
+        let _ = minimize_window(&matrix, usize::MAX / 2 + 1, 0..2, 0..2);
+    }
+
+    #[test]
+    fn test_shuffled_worst_case() {
+        for &max in &[1, 2, 5, 10, 25] {
+            let matrix = ::product_matrix(max);
+            let expected = (0..max).map(Some).rev().collect::<Vec<_>>();
+            for seed in 0..2 {
+                assert_eq!(minimize_shuffled(&matrix, max, max, seed), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shuffled_staircase() {
+        for &max in &[1, 2, 5, 50, 100] {
+            let matrix = ::staircase_matrix(max);
+            let settled = max / 2;
+            let expected = (0..max)
+                .map(|i| Some(if i < settled { i } else { max - 1 - (i - settled) }))
+                .collect::<Vec<_>>();
+
+            assert_eq!(minimize(&matrix, max, max), expected);
+            let ordered = ITERATIONS.with(|n| n.get());
+            for seed in 0..4 {
+                assert_eq!(minimize_shuffled(&matrix, max, max, seed), expected);
+                let shuffled = ITERATIONS.with(|n| n.get());
+                if max >= 50 {
+                    assert!(2 * shuffled < ordered, "{} against {} iterations for N = {}", shuffled, ordered, max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shuffled_rectangle() {
+        let matrix = vec![
+             0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+            53, 207, 256, 207, 231, 348, 348, 348, 231, 244, 244,
+           240,  33,  67,  33,  56, 133, 133, 133,  56,  33,  33,
+           460, 107, 200, 107, 122, 324, 324, 324, 122,  33,  33,
+           167, 340, 396, 340, 422, 567, 567, 567, 422, 442, 442,
+           167, 367, 307, 367, 433, 336, 336, 336, 433, 158, 158,
+           160,  20,  37,  20,  31,  70,  70,  70,  31,  22,  22,
+           200, 307, 393, 307, 222, 364, 364, 364, 222, 286, 286,
+           33 , 153, 152, 153, 228, 252, 252, 252, 228,  78,  78,
+           93 , 140, 185, 140,  58, 118, 118, 118,  58,  44,  44,
+           0  ,   7,  22,   7,  19,  58,  58,  58,  19,   0,   0,
+           67 , 153, 241, 153, 128, 297, 297, 297, 128,  39,  39,
+           73 , 253, 389, 253, 253, 539, 539, 539, 253,  36,  36,
+           173, 267, 270, 267, 322, 352, 352, 352, 322, 231, 231,
+        ];
+        for seed in 0..8 {
+            let assignment = minimize_shuffled(&matrix, 14, 11, seed);
+            assert_eq!(assignment.len(), 14);
+            assert_eq!(
+                828,
+                assignment
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, &v)| v.map(|j| matrix[index!(11, i, j)]))
+                    .sum::<u64>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_optimal_alternative() {
+        // Both optima of a tied matrix are accepted
+        let matrix = vec![
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 1, 2,
+            0, 0, 3, 4,
+        ];
+        assert!(is_optimal(&matrix, 4, 4, &[Some(3), Some(2), Some(1), Some(0)]));
+        assert!(is_optimal(&matrix, 4, 4, &[Some(2), Some(3), Some(0), Some(1)]));
+        assert!(!is_optimal(&matrix, 4, 4, &[Some(0), Some(1), Some(2), Some(3)]));
+    }
+
+    #[test]
+    fn test_is_optimal_invalid() {
+        let matrix = vec![
+            34, 26, 17, 12,
+            43, 43, 36, 10,
+            97, 47, 66, 34,
+            52, 42, 19, 36,
+            15, 93, 55, 80
+        ];
+        assert!(is_optimal(&matrix, 5, 4, &[Some(1), Some(3), None, Some(2), Some(0)]));
+
+        // Wrong length
+        assert!(!is_optimal(&matrix, 5, 4, &[Some(1), Some(3), None, Some(2)]));
+
+        // Out of bounds column
+        assert!(!is_optimal(&matrix, 5, 4, &[Some(1), Some(3), None, Some(2), Some(4)]));
+
+        // Repeated column
+        assert!(!is_optimal(&matrix, 5, 4, &[Some(1), Some(3), None, Some(1), Some(0)]));
+
+        // Not maximal, even though it's cheaper
+        assert!(!is_optimal(&matrix, 5, 4, &[Some(1), Some(3), None, None, Some(0)]));
+    }
+
+    #[test]
+    fn test_reduced_cost_matrix() {
+        let fixtures = vec![
+            (vec![
+                250, 400, 350,
+                400, 600, 350,
+                200, 400, 250,
+            ], 3, 3),
+            (vec![
+                400, 150, 400, 1,
+                400, 450, 600, 2,
+                300, 225, 300, 3,
+            ], 3, 4),
+            (vec![
+                34, 26, 17, 12,
+                43, 43, 36, 10,
+                97, 47, 66, 34,
+                52, 42, 19, 36,
+                15, 93, 55, 80
+            ], 5, 4),
+        ];
+        for (matrix, height, width) in fixtures {
+            let reduced = reduced_cost_matrix(&matrix, height, width);
+            assert_eq!(reduced.dim(), (height, width));
+            assert!(reduced.iter().all(|&c| c >= 0));
+            for (i, j) in minimize(&matrix, height, width).into_iter().enumerate() {
+                if let Some(j) = j { assert_eq!(reduced[(i, j)], 0) }
+            }
+        }
+    }
+
+    #[test]
+    fn test_monomorphic_wrappers() {
+        let matrix = vec![
+            12,  9, 27, 10, 23,
+             7, 13, 13, 30, 19,
+            25, 18, 26, 11, 26,
+             9, 28, 26, 23, 13,
+            16, 16, 24,  6,  9,
+        ];
+        let expected = minimize(&matrix, 5, 5);
+        assert_eq!(minimize_u32(&matrix, 5, 5), expected);
+        let matrix = matrix.into_iter().map(|c| c as usize).collect::<Vec<_>>();
+        assert_eq!(minimize_usize(&matrix, 5, 5), expected);
+    }
+
+    #[test]
+    fn test_problem_default() {
+        let fixtures = vec![
+            (vec![
+                250, 400, 350,
+                400, 600, 350,
+                200, 400, 250,
+            ], 3, 3),
+            (vec![
+                400, 150, 400, 1,
+                400, 450, 600, 2,
+                300, 225, 300, 3,
+            ], 3, 4),
+            (vec![
+                34, 26, 17, 12,
+                43, 43, 36, 10,
+                97, 47, 66, 34,
+                52, 42, 19, 36,
+                15, 93, 55, 80
+            ], 5, 4),
+        ];
+        for (matrix, height, width) in fixtures {
+            let expected = minimize(&matrix, height, width);
+            let assignment = AssignmentProblem::new(&matrix, height, width).solve().unwrap();
+            assert_eq!(assignment.cost(), total_cost(&matrix, width, &expected));
+            assert!(is_optimal(&matrix, height, width, assignment.rows()));
+        }
+    }
+
+    #[test]
+    fn test_problem_maximize() {
+        let matrix = vec![
+            400, 150, 400, 1,
+            400, 450, 600, 2,
+            300, 225, 300, 3,
+        ];
+        let assignment = AssignmentProblem::new(&matrix, 3, 4)
+            .maximize()
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.cost(), 1225);
+    }
+
+    #[test]
+    fn test_problem_gate() {
+        let matrix = vec![
+             1, 50, 60,
+            70,  2, 80,
+            90, 99,  3,
+        ];
+
+        // Matching the last row would need an entry above the gate
+        let assignment = AssignmentProblem::new(&matrix, 3, 3)
+            .forbid(2, 2)
+            .gate(90)
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(2), Some(1), Some(0)]);
+
+        let assignment = AssignmentProblem::new(&matrix, 3, 3)
+            .forbid(2, 2)
+            .gate(60)
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(0), Some(1), None]);
+        assert_eq!(assignment.cost(), 3);
+
+        // Gating profit from below when maximizing
+        let assignment = AssignmentProblem::new(&matrix, 3, 3)
+            .maximize()
+            .gate(75)
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[None, Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_problem_forbid_maximal() {
+        // Row 1 can only use column 0, which row 0 also prefers:
+        // matching both rows takes priority over the cheaper single match.
+        let matrix = vec![
+            1, 9,
+            5, 1,
+        ];
+        let assignment = AssignmentProblem::new(&matrix, 2, 2)
+            .forbid(1, 1)
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(1), Some(0)]);
+        assert_eq!(assignment.cost(), 14);
+    }
+
+    #[test]
+    fn test_problem_lock() {
+        let matrix = vec![
+            250, 400, 350,
+            400, 600, 350,
+            200, 400, 250,
+        ];
+        let assignment = AssignmentProblem::new(&matrix, 3, 3)
+            .lock(0, 0)
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(0), Some(2), Some(1)]);
+
+        assert_eq!(
+            AssignmentProblem::new(&matrix, 3, 3).lock(0, 0).lock(1, 0).solve(),
+            Err(SolveError::Infeasible(InfeasibleError))
+        );
+        assert_eq!(
+            AssignmentProblem::new(&matrix, 3, 3).forbid(0, 0).lock(0, 0).solve(),
+            Err(SolveError::Infeasible(InfeasibleError))
+        );
+    }
+
+    #[test]
+    fn test_problem_stable_ties() {
+        let matrix = vec![0; 16];
+        let assignment = AssignmentProblem::new(&matrix, 4, 4)
+            .stable_ties()
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(0), Some(1), Some(2), Some(3)]);
+
+        let matrix = vec![
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 1, 2,
+            0, 0, 3, 4,
+        ];
+        let assignment = AssignmentProblem::new(&matrix, 4, 4)
+            .stable_ties()
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(2), Some(3), Some(0), Some(1)]);
+
+        let matrix = vec![0; 12];
+        let assignment = AssignmentProblem::new(&matrix, 4, 3)
+            .stable_ties()
+            .solve()
+            .unwrap();
+        assert_eq!(assignment.rows(), &[Some(0), Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn test_problem_errors() {
+        let matrix = vec![1, 2, 3];
+        assert_eq!(
+            AssignmentProblem::new(&matrix, 2, 2).solve(),
+            Err(SolveError::Dimension(DimensionError::LengthMismatch { expected: 4, found: 3 }))
+        );
+        let matrix = vec![i32::MIN, i32::MAX];
+        assert_eq!(
+            AssignmentProblem::new(&matrix, 1, 2).maximize().solve(),
+            Err(SolveError::Overflow(OverflowError { row: 0, col: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_problem_maximize_excluded_sentinels() {
+        // Entries excluded by the gate or a forbid don't take part in the range
+        let matrix = vec![i32::MIN, 5, 5, i32::MIN];
+        let gated = AssignmentProblem::new(&matrix, 2, 2).maximize().gate(0).solve().unwrap();
+        assert_eq!(gated.rows(), &maximize_floored(&matrix, 2, 2, 0)[..]);
+        assert_eq!(gated.rows(), &[Some(1), Some(0)]);
+        assert_eq!(gated.cost(), 10);
+
+        let forbidden = AssignmentProblem::new(&matrix, 2, 2).maximize().forbid(0, 0).forbid(1, 1).solve().unwrap();
+        assert_eq!(forbidden.rows(), &[Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn test_scaled_f64_resolution() {
+        let matrix = vec![
+            0.4, 0.2,
+            0.1, 0.0,
+        ];
+
+        // Every cost rounds to zero, so the true optimum is lost
+        let coarse = minimize_scaled_f64(&matrix, 2, 2, 1.0);
+        assert_eq!(coarse, vec![Some(0), Some(1)]);
+
+        let fine = minimize_scaled_f64(&matrix, 2, 2, 10.0);
+        assert_eq!(fine, vec![Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn test_scaled_f64_matches_integer() {
+        let matrix = vec![
+            12,  9, 27, 10, 23,
+             7, 13, 13, 30, 19,
+            25, 18, 26, 11, 26,
+             9, 28, 26, 23, 13,
+            16, 16, 24,  6,  9,
+        ];
+        let floats = matrix.iter().map(|&c| f64::from(c) / 8.0).collect::<Vec<_>>();
+        assert_eq!(minimize_scaled_f64(&floats, 5, 5, 8.0), minimize(&matrix, 5, 5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scaled_f64_overflow() {
+        let matrix = vec![1e300, 0.0];
+        let _ = minimize_scaled_f64(&matrix, 1, 2, 1.0);
+    }
+
+    #[test]
+    fn test_single_row_ties() {
+        // Ties and clamped negatives go to the first minimum,
+        // just like the full algorithm.
+        let matrix = vec![3, -1, 0, 2, -5];
+        assert_eq!(minimize(&matrix, 1, 5), vec![Some(1)]);
+        assert_eq!(minimize(&matrix, 5, 1), vec![None, Some(0), None, None, None]);
+        let matrix = vec![4, 2, 2, 7];
+        assert_eq!(minimize(&matrix, 1, 4), vec![Some(1)]);
+        assert_eq!(minimize(&matrix, 4, 1), vec![None, Some(0), None, None]);
+        assert_eq!(
+            minimize_window(&matrix, 4, 0..1, 0..4),
+            minimize(&matrix, 1, 4)
+        );
+    }
+
+    /// Generates a random `height * width` matrix with entries in `lo..hi`.
+    fn random_matrix(state: &mut u64, height: usize, width: usize, lo: i64, hi: i64) -> Vec<i64> {
+        (0..height * width)
+            .map(|_| lo + (splitmix64(state) % (hi - lo) as u64) as i64)
+            .collect()
+    }
+
+    /// Computes the total cost of `assignment` without clamping.
+    fn raw_cost(matrix: &[i64], width: usize, assignment: &[Option<usize>]) -> i64 {
+        assignment.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| matrix[index!(width, i, j)]))
+            .sum()
+    }
+
+    /// Calls `f` with every matching of a `height * width` matrix, as an assignment
+    /// in the format of `minimize`, for brute-force comparisons on small inputs.
+    /// With `maximal`, only matchings of `min(height, width)` rows are visited,
+    /// and otherwise every partial matching is, including the empty one.
+    fn for_each_matching<F: FnMut(&[Option<usize>])>(height: usize, width: usize, maximal: bool, mut f: F) {
+        fn visit<F: FnMut(&[Option<usize>])>(
+            i: usize,
+            left: usize,
+            maximal: bool,
+            used: &mut Vec<bool>,
+            current: &mut Vec<Option<usize>>,
+            f: &mut F,
+        ) {
+            let height = current.len();
+            if i == height {
+                if !maximal || left == 0 { f(current) }
+                return
+            }
+            if !maximal || height - i > left {
+                visit(i + 1, left, maximal, used, current, f);
+            }
+            if left == 0 { return }
+            for j in 0..used.len() {
+                if used[j] { continue }
+                used[j] = true;
+                current[i] = Some(j);
+                visit(i + 1, left - 1, maximal, used, current, f);
+                current[i] = None;
+                used[j] = false;
+            }
+        }
+
+        let left = height.min(width);
+        visit(0, left, maximal, &mut vec![false; width], &mut vec![None; height], &mut f);
+    }
+
+    /// Collects the costs of the entries chosen by `assignment`, row by row.
+    fn assigned<T: Copy>(matrix: &[T], width: usize, assignment: &[Option<usize>]) -> Vec<T> {
+        assignment.iter()
+            .enumerate()
+            .filter_map(|(i, &j)| j.map(|j| matrix[index!(width, i, j)]))
+            .collect()
+    }
+
+    #[test]
+    fn test_for_each_matching() {
+        // 3 * 2 maximal matchings of two rows into three columns, and
+        // 1 + 2 * 3 + 3 * 2 partial ones
+        let mut maximal = Vec::new();
+        for_each_matching(2, 3, true, |a| maximal.push(a.to_vec()));
+        assert_eq!(maximal.len(), 6);
+        assert!(maximal.iter().all(|a| a.iter().all(|j| j.is_some()) && a[0] != a[1]));
+
+        let mut partial = 0;
+        for_each_matching(2, 3, false, |_| partial += 1);
+        assert_eq!(partial, 13);
+
+        let mut tall = Vec::new();
+        for_each_matching(3, 1, true, |a| tall.push(a.to_vec()));
+        assert_eq!(tall, vec![
+            vec![None, None, Some(0)],
+            vec![None, Some(0), None],
+            vec![Some(0), None, None],
+        ]);
+    }
+
+    #[test]
+    fn test_maximize_minimize_duality() {
+        let mut state = 0;
+        for _ in 0..500 {
+            let height = 1 + (splitmix64(&mut state) % 8) as usize;
+            let width = 1 + (splitmix64(&mut state) % 8) as usize;
+            let matrix = random_matrix(&mut state, height, width, -50, 50);
+
+            let max = *matrix.iter().max().unwrap();
+            let negated = matrix.iter().map(|&v| max - v).collect::<Vec<_>>();
+
+            let maximized = maximize(&matrix, height, width);
+            let minimized = minimize(&negated, height, width);
+            let built = AssignmentProblem::new(&matrix, height, width)
+                .maximize()
+                .solve()
+                .unwrap();
+
+            let expected = raw_cost(&matrix, width, &minimized);
+            assert_eq!(raw_cost(&matrix, width, &maximized), expected);
+            assert_eq!(built.cost(), expected);
+            assert_eq!(maximized.iter().filter(|j| j.is_some()).count(), height.min(width));
+        }
+    }
+
+    #[test]
+    fn test_column_groups_constrained() {
+        let matrix = vec![
+            1, 2, 9, 8,
+            2, 1, 9, 9,
+            9, 9, 3, 4,
+        ];
+        assert_eq!(
+            minimize(&matrix, 3, 4),
+            vec![Some(0), Some(1), Some(2)]
+        );
+
+        // Columns 0 and 1 can't both be used
+        assert_eq!(
+            minimize_with_column_groups(&matrix, 3, 4, &[0, 0, 1, 2]),
+            vec![Some(3), Some(1), Some(2)]
+        );
+
+        // Singleton groups reduce to `minimize`
+        assert_eq!(
+            minimize_with_column_groups(&matrix, 3, 4, &[3, 2, 1, 0]),
+            minimize(&matrix, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_column_groups_fewer_groups() {
+        let matrix = vec![
+            5, 3, 8,
+            4, 6, 1,
+            7, 2, 9,
+        ];
+        // A single group admits only one assigned row
+        assert_eq!(
+            minimize_with_column_groups(&matrix, 3, 3, &[4, 4, 4]),
+            vec![None, Some(2), None]
+        );
+    }
+
+    #[test]
+    fn test_stars_match_assignment() {
+        let matrix = vec![
+             0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+            53, 207, 256, 207, 231, 348, 348, 348, 231, 244, 244,
+           240,  33,  67,  33,  56, 133, 133, 133,  56,  33,  33,
+           460, 107, 200, 107, 122, 324, 324, 324, 122,  33,  33,
+           167, 340, 396, 340, 422, 567, 567, 567, 422, 442, 442,
+           167, 367, 307, 367, 433, 336, 336, 336, 433, 158, 158,
+           160,  20,  37,  20,  31,  70,  70,  70,  31,  22,  22,
+           200, 307, 393, 307, 222, 364, 364, 364, 222, 286, 286,
+           33 , 153, 152, 153, 228, 252, 252, 252, 228,  78,  78,
+           93 , 140, 185, 140,  58, 118, 118, 118,  58,  44,  44,
+           0  ,   7,  22,   7,  19,  58,  58,  58,  19,   0,   0,
+           67 , 153, 241, 153, 128, 297, 297, 297, 128,  39,  39,
+           73 , 253, 389, 253, 253, 539, 539, 539, 253,  36,  36,
+           173, 267, 270, 267, 322, 352, 352, 352, 322, 231, 231,
+        ];
+        for &(height, width) in &[(14, 11), (11, 14)] {
+            let (assignment, stars) = minimize_with_stars(&matrix, height, width);
+            assert_eq!(assignment, minimize(&matrix, height, width));
+            assert_eq!(stars.len(), 11);
+            let expected = assignment.iter()
+                .enumerate()
+                .filter_map(|(i, &j)| j.map(|j| (i, j)))
+                .collect::<Vec<_>>();
+            assert_eq!(stars, expected);
+        }
+    }
+
+    #[test]
+    fn test_owned_matches_borrowed() {
+        let matrix = vec![
+            82, 83, 69, 92, 100,
+            77, 37, 49, 92, 195,
+            11, 69,  5, 86,  93,
+             8,  9, 98, 23, 106,
+        ];
+        for &(height, width) in &[(4, 5), (5, 4), (1, 20), (20, 1), (2, 10), (3, 3)] {
+            assert_eq!(
+                minimize_owned(matrix.clone(), height, width),
+                minimize(&matrix, height, width)
+            );
+        }
+    }
+
+    #[test]
+    fn test_owned_negative() {
+        let matrix = vec![
+            -5, 3, 2,
+             4, -1, 6,
+        ];
+        assert_eq!(
+            minimize_owned(matrix.clone(), 2, 3),
+            minimize(&matrix, 2, 3)
+        );
+    }
+
+
+    #[test]
+    fn test_worst_case_iterations() {
+        // Each pass of the main loop either augments the matching (at most N
+        // times), covers a new row in [Step 4], or runs [Step 6], which always
+        // uncovers a new zero. Between augmentations there are at most N of each
+        // of the latter two, so the loop runs at most N * (2N + 1) + 1 times.
+        for max in 1..50 {
+            let matrix = ::product_matrix(max);
+            assert_eq!(minimize(&matrix, max, max).len(), max);
+            let iterations = ITERATIONS.with(|n| n.get());
+            assert!(iterations <= max * (2 * max + 1) + 1, "{} iterations for N = {}", iterations, max);
+        }
+    }
+
+
+    #[test]
+    fn test_max_weight_matching() {
+        let matrix = vec![
+            82, 83, 69, 92, 100,
+            77, 37, 49, 92, 195,
+            11, 69,  5, 86,  93,
+             8,  9, 98, 23, 106,
+        ];
+        for &(left, right) in &[(4, 5), (5, 4), (3, 3), (1, 5)] {
+            let weight = |u: usize, v: usize| matrix[(u * 5 + v) % matrix.len()];
+            let dense = (0..left)
+                .flat_map(|u| (0..right).map(move |v| (u, v)))
+                .map(|(u, v)| weight(u, v))
+                .collect::<Vec<_>>();
+            assert_eq!(max_weight_matching(left, right, weight), maximize(&dense, left, right));
+        }
+    }
+
+
+    #[test]
+    fn test_solver_reuse() {
+        let mut state = 0x5eed;
+        let mut solver = Solver::new();
+        for &(height, width) in &[(4, 4), (2, 7), (7, 2), (1, 5), (5, 1), (6, 6), (3, 3)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            assert_eq!(solver.minimize(&matrix, height, width), minimize(&matrix, height, width));
+        }
+    }
+
+    #[test]
+    fn test_solver_required_bytes() {
+        let mut state = 0xb17e5;
+        for &(height, width) in &[(2, 2), (3, 40), (40, 3), (33, 33), (1, 8), (8, 1)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let required = Solver::<i64>::required_bytes(height, width);
+            let output = height * mem::size_of::<Option<usize>>();
+
+            let mut solver = Solver::new();
+            let result = solver.minimize(&matrix, height, width);
+            let used = result.capacity() * mem::size_of::<Option<usize>>();
+            assert_eq!(solver.allocated_bytes() + used, required);
+
+            let reserved = Solver::<i64>::with_capacity(height, width).allocated_bytes();
+            assert_eq!(reserved + output, required);
+        }
+    }
+
+
+    #[test]
+    fn test_cost_only() {
+        let mut state = 0xc057;
+        for &(height, width) in &[(0, 4), (1, 6), (6, 1), (4, 4), (3, 8), (8, 3), (10, 10)] {
+            let matrix = random_matrix(&mut state, height, width, -20, 100);
+            let assignment = minimize(&matrix, height, width);
+            assert_eq!(
+                minimize_cost_only(&matrix, height, width),
+                total_cost(&matrix, width, &assignment)
+            );
+        }
+    }
+
+
+    #[test]
+    fn test_feasible_sentinel_row() {
+        let x = u64::MAX;
+        let matrix = vec![
+            82, 83, 69, 92,
+            77, 37, 49, 92,
+             x,  x,  x,  x,
+            11, 69,  5, 86,
+             8,  9, 98, 23,
+        ];
+        let result = minimize_feasible(&matrix, 5, 4);
+        assert_eq!(result[2], None);
+
+        // The remaining rows are solved exactly as if the sentinel row were absent
+        let mut rest = matrix[..8].to_vec();
+        rest.extend_from_slice(&matrix[12..]);
+        let expected = minimize(&rest, 4, 4);
+        assert_eq!(&result[..2], &expected[..2]);
+        assert_eq!(&result[3..], &expected[2..]);
+    }
+
+    #[test]
+    fn test_feasible_sentinel_column() {
+        let x = i32::MAX;
+        let matrix = vec![
+            1, x, 3,
+            x, x, x,
+            2, x, 1,
+        ];
+        assert_eq!(minimize_feasible(&matrix, 3, 3), vec![Some(0), None, Some(2)]);
+    }
+
+
+    #[test]
+    fn test_transpose_assignment() {
+        let mut state = 0x7a05;
+        for &(height, width) in &[(0, 3), (3, 0), (1, 5), (5, 1), (4, 7), (7, 4), (6, 6)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let assignment = minimize(&matrix, height, width);
+
+            let transposed_matrix = (0..width)
+                .flat_map(|j| (0..height).map(move |i| (i, j)))
+                .map(|(i, j)| matrix[index!(width, i, j)])
+                .collect::<Vec<_>>();
+            let transposed = transpose_assignment(&assignment, height, width);
+
+            assert_eq!(transposed.len(), width);
+            assert_eq!(
+                raw_cost(&transposed_matrix, height, &transposed),
+                raw_cost(&matrix, width, &assignment)
+            );
+            assert!(is_optimal(&transposed_matrix, width, height, &transposed));
+
+            // An empty problem has an empty assignment, which can't round-trip
+            if height > 0 && width > 0 {
+                assert_eq!(transpose_assignment(&transposed, width, height), assignment);
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_nonneg_rejects_buried_negative() {
+        let mut state = 0x9e6;
+        let mut matrix = random_matrix(&mut state, 100, 120, 0, 1000);
+        matrix[index!(120, 73, 41)] = -5;
+        assert_eq!(
+            try_minimize_nonneg(&matrix, 100, 120),
+            Err(NegativeCostError { row: 73, col: 41, value: -5 })
+        );
+
+        matrix[index!(120, 73, 41)] = 5;
+        assert_eq!(try_minimize_nonneg(&matrix, 100, 120), Ok(minimize(&matrix, 100, 120)));
+    }
+
+
+    #[test]
+    fn test_ordered_float() {
+        let matrix = [
+            0.5, 2.25, 1.0,
+            4.0, 5.5, 6.0,
+            7.0, 8.0, 9.75,
+        ];
+        let matrix = matrix.iter().map(|&c| OrderedFloat(c)).collect::<Vec<_>>();
+        assert_eq!(minimize_ordered(&matrix, 3, 3), vec![Some(2), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_ordered_float_matches_integers() {
+        let mut state = 0xf10a7;
+        for &(height, width) in &[(1, 4), (4, 1), (5, 5), (3, 7), (7, 3)] {
+            let matrix = random_matrix(&mut state, height, width, -10, 1000);
+            let floats = matrix.iter()
+                .map(|&c| OrderedFloat(c as f64 / 4.0))
+                .collect::<Vec<_>>();
+            assert_eq!(minimize_ordered(&floats, height, width), minimize(&matrix, height, width));
+        }
+    }
+
+
+    #[cfg(feature = "flow")]
+    #[test]
+    fn test_flow_matches_munkres() {
+        let mut state = 0xf70;
+        for &(height, width) in &[(0, 3), (1, 6), (6, 1), (5, 5), (4, 9), (9, 4), (12, 12)] {
+            for &percent in &[100, 50, 10] {
+                let mut matrix = random_matrix(&mut state, height, width, -10, 1000);
+                for cost in &mut matrix {
+                    if splitmix64(&mut state) % 100 >= percent {
+                        *cost = i64::MAX;
+                    }
+                }
+
+                let flow = ::minimize_flow(&matrix, height, width);
+                let munkres = minimize_feasible(&matrix, height, width);
+
+                assert_eq!(
+                    flow.iter().filter(|j| j.is_some()).count(),
+                    munkres.iter().filter(|j| j.is_some()).count()
+                );
+                assert_eq!(total_cost(&matrix, width, &flow), total_cost(&matrix, width, &munkres));
+                assert!(flow.iter().enumerate().all(|(i, &j)| {
+                    j.map(|j| matrix[index!(width, i, j)]) != Some(i64::MAX)
+                }));
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_optional_matches_dense() {
+        let mut state = 0x0971;
+        for &(height, width) in &[(1, 5), (5, 1), (4, 4), (3, 6), (6, 3)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let optional = matrix.iter().cloned().map(Some).collect::<Vec<_>>();
+            let result = minimize_optional(&optional, height, width);
+            let expected = minimize(&matrix, height, width);
+            assert_eq!(raw_cost(&matrix, width, &result), raw_cost(&matrix, width, &expected));
+        }
+    }
+
+    #[test]
+    fn test_optional_never_uses_none() {
+        let mut state = 0x0972;
+        for _ in 0..50 {
+            let matrix = random_matrix(&mut state, 6, 6, 0, 100)
+                .into_iter()
+                .map(|c| if splitmix64(&mut state) % 3 == 2 { None } else { Some(c) })
+                .collect::<Vec<_>>();
+            let result = minimize_optional(&matrix, 6, 6);
+            for (i, j) in result.iter().enumerate() {
+                if let Some(j) = *j { assert!(matrix[index!(6, i, j)].is_some()) }
+            }
+        }
+    }
+
+    #[test]
+    fn test_optional_maximum_cardinality() {
+        let matrix = vec![
+            Some(5), Some(1),
+            Some(2), None,
+            None,    Some(1),
+        ];
+        // Row 0 is cheapest on column 1, but then row 1 or 2 goes unmatched
+        let result = minimize_optional(&matrix, 3, 2);
+        assert_eq!(result, vec![None, Some(0), Some(1)]);
+    }
+
+
+    #[test]
+    fn test_sorted() {
+        let matrix = vec![
+            82, 83, 69, 92,
+            77, 37, 49, 92,
+            11, 69,  5, 86,
+             8,  9, 98, 23,
+        ];
+        assert_eq!(
+            minimize_sorted(&matrix, 4, 4),
+            vec![(2, 0, 11), (3, 3, 23), (1, 1, 37), (0, 2, 69)]
+        );
+    }
+
+    #[test]
+    fn test_sorted_rectangular() {
+        let mut state = 0x5027;
+        let matrix = random_matrix(&mut state, 7, 3, -50, 50);
+        let sorted = minimize_sorted(&matrix, 7, 3);
+        let assignment = minimize(&matrix, 7, 3);
+
+        assert_eq!(sorted.len(), 3);
+        assert!(sorted.windows(2).all(|w| w[0].2 <= w[1].2));
+        for &(i, j, cost) in &sorted {
+            assert_eq!(assignment[i], Some(j));
+            assert_eq!(matrix[index!(3, i, j)], cost);
+        }
+    }
+
+
+    #[test]
+    fn test_points_identical_sets() {
+        let mut state = 0x9017;
+        let a = (0..20)
+            .map(|_| {
+                let x = (splitmix64(&mut state) % 1000) as f64 / 7.0;
+                let y = (splitmix64(&mut state) % 1000) as f64 / 3.0;
+                [x, y]
+            })
+            .collect::<Vec<_>>();
+
+        // Reverse so the identity isn't the trivial diagonal
+        let b = a.iter().rev().cloned().collect::<Vec<_>>();
+        let expected = (0..20).rev().map(Some).collect::<Vec<_>>();
+        assert_eq!(minimize_points(&a, &b), expected);
+    }
+
+    #[test]
+    fn test_points_rectangular() {
+        let a = [[0.0, 0.0], [5.0, 5.0], [100.0, 100.0]];
+        let b = [[4.0, 4.0], [1.0, -1.0]];
+        assert_eq!(minimize_points(&a, &b), vec![Some(1), Some(0), None]);
+        assert_eq!(minimize_points(&b, &a), vec![Some(1), Some(0)]);
+        assert_eq!(minimize_points(&a, &[]), vec![None; 3]);
+    }
+
+
+    #[test]
+    fn test_explained_dense() {
+        let mut state = 0xe4;
+        let matrix = random_matrix(&mut state, 6, 4, 0, 100);
+        let optional = matrix.iter().cloned().map(Some).collect::<Vec<_>>();
+        let explained = minimize_explained(&optional, 6, 4);
+
+        let columns = explained.iter().map(RowAssignment::column).collect::<Vec<_>>();
+        let expected = minimize(&matrix, 6, 4);
+        assert_eq!(raw_cost(&matrix, 4, &columns), raw_cost(&matrix, 4, &expected));
+
+        let surplus = RowAssignment::Unmatched { reason: Reason::Surplus };
+        assert_eq!(explained.iter().filter(|&&r| r == surplus).count(), 2);
+    }
+
+    #[test]
+    fn test_explained_forbidden_and_surplus() {
+        // Rows 0 and 1 can only take column 0, so one of them is surplus
+        // even though there are as many columns as rows
+        let matrix = vec![
+            Some(1), None,    None,
+            Some(2), None,    None,
+            None,    None,    None,
+        ];
+        assert_eq!(
+            minimize_explained(&matrix, 3, 3),
+            vec![
+                RowAssignment::Matched(0),
+                RowAssignment::Unmatched { reason: Reason::Surplus },
+                RowAssignment::Unmatched { reason: Reason::Forbidden },
+            ]
+        );
+    }
+
+
+    #[test]
+    fn test_analyze_wikipedia_4x4() {
+        let matrix = vec![
+            0, 1, 2, 3,
+            4, 5, 6, 0,
+            0, 2, 4, 5,
+            3, 0, 0, 9,
+        ];
+        assert_eq!(analyze(&matrix, 4, 4), MatrixStats {
+            min: Some(0),
+            max: Some(9),
+            zeros: 5,
+            rows_without_zero: 0,
+            cols_without_zero: 0,
+            square: true,
+        });
+    }
+
+    #[test]
+    fn test_analyze_rectangle_4x5() {
+        let matrix = vec![
+            82, 83, 69, 92, 100,
+            77, 37, 49, 92, 195,
+            11, 69,  5, 86,  93,
+             8,  9, 98, 23, 106,
+        ];
+        assert_eq!(analyze(&matrix, 4, 5), MatrixStats {
+            min: Some(5),
+            max: Some(195),
+            zeros: 0,
+            rows_without_zero: 4,
+            cols_without_zero: 5,
+            square: false,
+        });
+    }
+
+    #[test]
+    fn test_analyze_empty() {
+        let stats = analyze::<u32>(&[], 0, 3);
+        assert_eq!((stats.min, stats.max, stats.zeros), (None, None, 0));
+        assert_eq!((stats.rows_without_zero, stats.cols_without_zero), (0, 3));
+    }
+
+
+    #[test]
+    fn test_slack_leaves_expensive_row() {
+        let matrix = vec![
+             3, 90,
+            80,  2,
+        ];
+        // Matching both rows costs 5, and swapping them costs 170
+        assert_eq!(minimize_with_slack(&matrix, 2, 2, 50, 50), (vec![Some(0), Some(1)], 5));
+
+        // With cheap slack, leaving everything unmatched is best
+        assert_eq!(minimize_with_slack(&matrix, 2, 2, 1, 0), (vec![None, None], 2));
+
+        let matrix = vec![
+             3, 90,
+            80, 85,
+        ];
+        // Row 1 is expensive everywhere: unmatched, it costs 10 + 5 for column 1
+        assert_eq!(minimize_with_slack(&matrix, 2, 2, 10, 5), (vec![Some(0), None], 18));
+    }
+
+    #[test]
+    fn test_slack_zero_penalties_matches_nothing() {
+        let mut state = 0x51ac;
+        let matrix = random_matrix(&mut state, 4, 6, 1, 100);
+        assert_eq!(minimize_with_slack(&matrix, 4, 6, 0, 0), (vec![None; 4], 0));
+    }
+
+    #[test]
+    fn test_slack_large_penalties_matches_minimize() {
+        let mut state = 0x51ad;
+        for &(height, width) in &[(3, 3), (4, 6), (6, 4)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let (result, cost) = minimize_with_slack(&matrix, height, width, 1000, 0);
+            let expected = minimize(&matrix, height, width);
+            let unmatched = (height - height.min(width)) as i64;
+            assert_eq!(raw_cost(&matrix, width, &result), raw_cost(&matrix, width, &expected));
+            assert_eq!(cost, raw_cost(&matrix, width, &expected) + 1000 * unmatched);
+        }
+    }
+
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace() {
+        let mut state = 0x7ace;
+        for &(height, width) in &[(1, 4), (4, 4), (3, 6), (6, 3)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 20);
+            let (assignment, trace) = ::minimize_trace(&matrix, height, width);
+            let expected = minimize(&matrix, height, width);
+            assert_eq!(raw_cost(&matrix, width, &assignment), raw_cost(&matrix, width, &expected));
+
+            // Starts from the clamped input, and only enters valid steps
+            assert_eq!(trace[0].step, 1);
+            assert_eq!(trace[0].costs.as_slice().unwrap(), &matrix[..]);
+            assert!(trace.iter().all(|s| 1 <= s.step && s.step <= 6));
+
+            for snapshot in &trace {
+                assert_eq!(snapshot.costs.dim(), (height, width));
+                assert_eq!(snapshot.stars.dim(), (height, width));
+                assert_eq!(snapshot.row_cover.len(), height);
+                assert_eq!(snapshot.col_cover.len(), width);
+            }
+
+            // The final stars are the assignment
+            let last = trace.last().unwrap();
+            if last.step == 3 {
+                for (i, j) in assignment.iter().enumerate() {
+                    if let Some(j) = *j { assert!(last.stars[(i, j)]) }
+                }
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_problem_from_array() {
+        let mut state = 0xa22a;
+        for &(height, width) in &[(3, 3), (2, 5), (5, 2)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 50);
+            let array = Array2::from_shape_vec((height, width), matrix.clone()).unwrap();
+
+            let assignment = AssignmentProblem::from(&array).solve().unwrap();
+            let rows = (&assignment).into_iter().cloned().collect::<Vec<_>>();
+            let vec: Vec<Option<usize>> = assignment.clone().into();
+
+            assert_eq!(rows, vec);
+            assert_eq!(assignment.into_iter().collect::<Vec<_>>(), vec);
+
+            let expected = minimize(&matrix, height, width);
+            assert_eq!(raw_cost(&matrix, width, &vec), raw_cost(&matrix, width, &expected));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_problem_from_transposed_array() {
+        let array = Array2::from_shape_vec((2, 3), vec![1, 2, 3, 4, 5, 6])
+            .unwrap()
+            .reversed_axes();
+        let _ = AssignmentProblem::from(&array);
+    }
+
+
+    #[test]
+    fn test_csr_matches_dense() {
+        let mut state = 0xc52;
+        for &(height, width) in &[(1, 5), (5, 1), (6, 6), (4, 9), (9, 4), (20, 20)] {
+            for &percent in &[100, 30, 5] {
+                let dense = random_matrix(&mut state, height, width, 0, 1000)
+                    .into_iter()
+                    .map(|c| if splitmix64(&mut state) % 100 < percent { Some(c) } else { None })
+                    .collect::<Vec<_>>();
+
+                let (mut values, mut col_indices, mut row_ptr) = (Vec::new(), Vec::new(), vec![0]);
+                for i in 0..height {
+                    for j in 0..width {
+                        if let Some(c) = dense[index!(width, i, j)] {
+                            values.push(c);
+                            col_indices.push(j);
+                        }
+                    }
+                    row_ptr.push(values.len());
+                }
+
+                let sparse = minimize_csr(&values, &col_indices, &row_ptr, height, width);
+                let expected = minimize_optional(&dense, height, width);
+                let cost = |assignment: &[Option<usize>]| -> i64 {
+                    assignment.iter()
+                        .enumerate()
+                        .filter_map(|(i, &j)| j.map(|j| dense[index!(width, i, j)].unwrap()))
+                        .sum()
+                };
+
+                assert_eq!(
+                    sparse.iter().filter(|j| j.is_some()).count(),
+                    expected.iter().filter(|j| j.is_some()).count()
+                );
+                assert_eq!(cost(&sparse), cost(&expected));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_csr_column_out_of_bounds() {
+        let _ = minimize_csr(&[1, 2], &[0, 3], &[0, 1, 2], 2, 3);
+    }
+
+
+    #[test]
+    fn test_iter_matches_minimize() {
+        let mut state = 0x17e2;
+        for &(height, width) in &[(0, 3), (3, 0), (1, 6), (6, 1), (5, 5), (3, 7), (7, 3)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 10);
+            let pairs = minimize_iter(&matrix, height, width).collect::<Vec<_>>();
+            let expected = minimize(&matrix, height, width)
+                .into_iter()
+                .enumerate()
+                .collect::<Vec<_>>();
+            assert_eq!(pairs, expected);
+        }
+    }
+
+
+    #[test]
+    fn test_minimize_as_widens() {
+        let matrix: Vec<u16> = vec![
+            65535,     0,     1,
+                0, 65535, 65535,
+                1, 65534, 65534,
+        ];
+        let wide = matrix.iter().map(|&c| u64::from(c)).collect::<Vec<_>>();
+
+        // Solving needs reduced costs that don't fit in a u16
+        assert!(reduced_cost_matrix(&wide, 3, 3).iter().any(|&c| c > u64::from(u16::MAX)));
+
+        assert_eq!(minimize_as::<u16, u64>(&matrix, 3, 3), vec![Some(1), Some(0), Some(2)]);
+        assert_eq!(minimize_as::<u16, u64>(&matrix, 3, 3), minimize(&wide, 3, 3));
+    }
+
+    #[test]
+    fn test_minimize_as_signed() {
+        let mut state = 0xa5;
+        let matrix = random_matrix(&mut state, 4, 6, -100, 100)
+            .into_iter()
+            .map(|c| c as i8)
+            .collect::<Vec<_>>();
+        let wide = matrix.iter().map(|&c| i64::from(c)).collect::<Vec<_>>();
+        assert_eq!(minimize_as::<i8, i64>(&matrix, 4, 6), minimize(&wide, 4, 6));
+        assert_eq!(minimize_as::<i8, i64>(&matrix, 6, 4), minimize(&wide, 6, 4));
+    }
+
+
+    #[test]
+    fn test_canonicalize() {
+        let matrix = vec![0; 12];
+        let canonical = vec![Some(0), Some(1), Some(2), None];
+        assert_eq!(canonicalize(&minimize(&matrix, 4, 3), &matrix, 4, 3), canonical);
+        assert_eq!(canonicalize(&[None, Some(2), Some(0), Some(1)], &matrix, 4, 3), canonical);
+
+        let matrix = vec![
+            0, 0, 0, 0,
+            0, 0, 0, 0,
+            0, 0, 1, 2,
+            0, 0, 3, 4,
+        ];
+        assert_eq!(
+            canonicalize(&minimize(&matrix, 4, 4), &matrix, 4, 4),
+            vec![Some(2), Some(3), Some(0), Some(1)]
+        );
+
+        assert_eq!(canonicalize::<i32>(&[None, None], &[], 2, 0), vec![None, None]);
+
+        let mut state = 0x67;
+        for _ in 0..50 {
+            let matrix = random_matrix(&mut state, 5, 4, 0, 3);
+            let expected = canonicalize(&minimize(&matrix, 5, 4), &matrix, 5, 4);
+            assert!(is_optimal(&matrix, 5, 4, &expected));
+            assert_eq!(canonicalize(&minimize_shuffled(&matrix, 5, 4, 7), &matrix, 5, 4), expected);
+            assert_eq!(canonicalize(&expected, &matrix, 5, 4), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not optimal")]
+    fn test_canonicalize_suboptimal() {
+        let matrix = vec![
+            1, 2,
+            2, 1,
+        ];
+        let _ = canonicalize(&[Some(1), Some(0)], &matrix, 2, 2);
+    }
+
+
+    #[test]
+    fn test_minimize_then_maximize() {
+        let cost = vec![0; 4];
+        let secondary = vec![
+            1, 5,
+            5, 1,
+        ];
+        assert_eq!(minimize_then_maximize(&cost, &secondary, 2, 2), vec![Some(1), Some(0)]);
+
+        // Secondary never trades off against the primary cost
+        let cost = vec![
+            0, 1,
+            1, 0,
+        ];
+        assert_eq!(minimize_then_maximize(&cost, &secondary, 2, 2), vec![Some(0), Some(1)]);
+
+        // Rectangular: rows 0 and 1 tie on columns 0 and 2
+        let cost = vec![
+            2, 7, 2,
+            2, 7, 2,
+        ];
+        let secondary = vec![
+            0, 9, 4,
+            6, 9, 3,
+        ];
+        assert_eq!(minimize_then_maximize(&cost, &secondary, 2, 3), vec![Some(2), Some(0)]);
+
+        let cost = vec![
+            2, 2,
+            7, 7,
+            2, 2,
+        ];
+        let secondary = vec![
+            0, 6,
+            9, 9,
+            4, 3,
+        ];
+        assert_eq!(minimize_then_maximize(&cost, &secondary, 3, 2), vec![Some(1), None, Some(0)]);
+
+        let secondary = vec![-3, 3, -1, 1];
+        assert_eq!(minimize_then_maximize(&[0; 4], &secondary, 2, 2), vec![Some(1), Some(0)]);
+        assert_eq!(minimize_then_maximize::<i32>(&[], &[], 2, 0), vec![None, None]);
+    }
+
+
+    #[test]
+    fn test_signed_min_value() {
+        let min = i32::MIN;
+        let matrix = vec![
+            min, 5, 3,
+            4, min, 2,
+            1, 6, min,
+        ];
+
+        // Clamped to zero before any arithmetic
+        assert_eq!(minimize(&matrix, 3, 3), vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(minimize_cost_only(&matrix, 3, 3), 0);
+        assert_eq!(minimize_as::<i32, i64>(&matrix, 3, 3), vec![Some(0), Some(1), Some(2)]);
+        assert!(is_optimal(&matrix, 3, 3, &[Some(0), Some(1), Some(2)]));
+
+        // `max - min` doesn't fit unless every entry is negative
+        assert_eq!(try_maximize(&matrix, 3, 3), Err(OverflowError { row: 0, col: 0 }));
+        assert_eq!(try_maximize(&[min, -1], 1, 2), Ok(vec![Some(1)]));
+        assert_eq!(
+            try_max_weight_matching(3, 3, |u, v| matrix[3 * u + v]),
+            Err(OverflowError { row: 0, col: 0 })
+        );
+        assert_eq!(
+            try_minimize_then_maximize(&[0; 9], &matrix, 3, 3),
+            Err(OverflowError { row: 0, col: 0 })
+        );
+        assert_eq!(
+            try_minimize_then_maximize(&matrix, &[-1, -1, -1, -1, -1, -1, -1, -1, min], 3, 3),
+            Ok(vec![Some(0), Some(1), Some(2)])
+        );
+        assert_eq!(
+            AssignmentProblem::new(&matrix, 3, 3).maximize().solve(),
+            Err(SolveError::Overflow(OverflowError { row: 0, col: 0 }))
+        );
+    }
+
+
+    #[test]
+    fn test_max_safe_cost() {
+        assert_eq!(max_safe_cost::<u8>(4, 4), 31);
+        assert_eq!(max_safe_cost::<u8>(2, 7), 63);
+        assert_eq!(max_safe_cost::<i8>(1, 3), 63);
+        assert_eq!(max_safe_cost::<u8>(200, 200), 0);
+        assert_eq!(max_safe_cost::<u32>(0, 5), u32::MAX);
+
+        // Overflow panics in debug builds, so solving at the boundary must not
+        for &(height, width) in &[(4, 4), (3, 5), (6, 2), (1, 3)] {
+            let cap = max_safe_cost::<u8>(height, width);
+            let mut state = (height * width) as u64;
+            for _ in 0..200 {
+                let matrix = random_matrix(&mut state, height, width, 0, cap as i64 + 1)
+                    .into_iter()
+                    .map(|c| c as u8)
+                    .collect::<Vec<_>>();
+                let wide = matrix.iter().map(|&c| i64::from(c)).collect::<Vec<_>>();
+                assert_eq!(
+                    i64::from(minimize_cost_only(&matrix, height, width)),
+                    minimize_cost_only(&wide, height, width)
+                );
+                assert!(is_optimal(&wide, height, width, &minimize(&matrix, height, width)));
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_minimize_k_best() {
+        let matrix = vec![
+            1, 2, 3,
+            2, 4, 6,
+            3, 6, 9,
+        ];
+
+        // Every one of the 3! permutations, in order of cost
+        let best = minimize_k_best(&matrix, 3, 3, 10);
+        let costs = best.iter().map(|&(_, cost)| cost).collect::<Vec<_>>();
+        assert_eq!(costs, vec![10, 11, 11, 13, 13, 14]);
+        for (assignment, cost) in &best {
+            assert_eq!(total_cost(&matrix, 3, assignment), *cost);
+        }
+        let mut distinct = best.iter().map(|(assignment, _)| assignment.clone()).collect::<Vec<_>>();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 6);
+        assert_eq!(minimize_k_best(&matrix, 3, 3, 2), best[..2].to_vec());
+
+        // Rectangular: choose 1 of 3 columns for each of 2 rows, in either orientation
+        let matrix = vec![
+            0, 5, 1,
+            4, 0, 9,
+        ];
+        let costs = minimize_k_best(&matrix, 2, 3, 10).into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+        assert_eq!(costs, vec![0, 1, 5, 9, 9, 14]);
+        let transposed = vec![0, 4, 5, 0, 1, 9];
+        let best = minimize_k_best(&transposed, 3, 2, 10);
+        assert_eq!(best.iter().map(|&(_, c)| c).collect::<Vec<_>>(), costs);
+        assert_eq!(best[1], (vec![None, Some(1), Some(0)], 1));
+
+        // Ties come out in the same order with or without the `rayon` feature
+        let best = minimize_k_best(&[0; 9], 3, 3, 6)
+            .into_iter()
+            .map(|(assignment, _)| assignment)
+            .collect::<Vec<_>>();
+        assert_eq!(best, vec![
+            vec![Some(0), Some(1), Some(2)],
+            vec![Some(1), Some(0), Some(2)],
+            vec![Some(0), Some(2), Some(1)],
+            vec![Some(2), Some(0), Some(1)],
+            vec![Some(1), Some(2), Some(0)],
+            vec![Some(2), Some(1), Some(0)],
+        ]);
+
+        assert_eq!(minimize_k_best(&matrix, 2, 3, 0), vec![]);
+        assert_eq!(minimize_k_best::<i32>(&[], 2, 0, 3), vec![(vec![None, None], 0)]);
+    }
+
+
+    #[test]
+    fn test_minimize_aggregate() {
+        let matrix = vec![
+            1, 2, 1,
+            4, 5, 6,
+            7, 8, 9,
+        ];
+        let (assignment, total) = minimize_aggregate(&matrix, 3, 3, |a, b| a + b, 0);
+        assert_eq!(assignment, minimize(&matrix, 3, 3));
+        assert_eq!(total, 13);
+
+        let matrix: Vec<i8> = vec![-5, 100, 100, 100, 100, -5];
+        let (assignment, total) = minimize_aggregate(&matrix, 3, 2, |a: i8, b| a.saturating_add(b), 0);
+        assert_eq!(assignment, vec![Some(0), None, Some(1)]);
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn test_minimize_product_brute_force() {
+        let mut state = 0x872;
+        for &(height, width) in &[(3, 3), (4, 4), (2, 5), (5, 3)] {
+            for _ in 0..50 {
+                let matrix = random_matrix(&mut state, height, width, 1, 30)
+                    .into_iter()
+                    .map(|c| c as f64 / 4.0)
+                    .collect::<Vec<_>>();
+                let assignment = minimize_product(&matrix, height, width);
+                let product = assigned(&matrix, width, &assignment).iter().product::<f64>();
+
+                // Smallest product over every maximal matching
+                let mut expected = f64::INFINITY;
+                for_each_matching(height, width, true, |a| {
+                    expected = expected.min(assigned(&matrix, width, a).iter().product());
+                });
+                assert_eq!(assignment.iter().filter(|j| j.is_some()).count(), height.min(width));
+                assert!((product - expected).abs() <= 1e-9 * expected, "{} != {}", product, expected);
+            }
+        }
+
+        assert_eq!(minimize_product(&[2.0; 4], 2, 2), vec![Some(0), Some(1)]);
+    }
+
+
+    #[test]
+    fn test_verify_potentials() {
+        // Recovers potentials from the reduced costs, fixing the first row's at zero
+        let mut state = 0x873;
+        for _ in 0..50 {
+            let matrix = random_matrix(&mut state, 4, 4, 0, 20);
+            let reduced = reduced_cost_matrix(&matrix, 4, 4);
+            let col_pot = (0..4).map(|j| matrix[j] - reduced[(0, j)]).collect::<Vec<_>>();
+            let row_pot = (0..4).map(|i| matrix[4 * i] - reduced[(i, 0)] - col_pot[0]).collect::<Vec<_>>();
+            let assignment = minimize(&matrix, 4, 4);
+            assert!(verify_potentials(&matrix, 4, 4, &row_pot, &col_pot, &assignment));
+            assert!(!verify_potentials(&matrix, 4, 4, &row_pot, &col_pot, &assignment[..3]));
+            assert!(!verify_potentials(&matrix, 4, 4, &row_pot, &col_pot[..3], &assignment));
+        }
+
+        // Rectangular: row 1 is unassigned, so its potential must be zero
+        let matrix = vec![
+            1, 4,
+            5, 6,
+            3, 2,
+        ];
+        let assignment = [Some(0), None, Some(1)];
+        assert!(verify_potentials(&matrix, 3, 2, &[0, 0, 0], &[1, 2], &assignment));
+        assert!(verify_potentials(&matrix, 3, 2, &[-1, 0, -1], &[2, 3], &assignment));
+        assert!(!verify_potentials(&matrix, 3, 2, &[-1, -1, -1], &[2, 3], &assignment));
+        assert!(!verify_potentials(&matrix, 3, 2, &[1, 0, 1], &[0, 1], &assignment));
+        assert!(!verify_potentials(&matrix, 3, 2, &[0, 0, 0], &[1, 2], &[Some(0), Some(1), None]));
+
+        let matrix = vec![1, 5, 3, 4, 6, 2];
+        assert!(verify_potentials(&matrix, 2, 3, &[1, 2], &[0, 0, 0], &[Some(0), Some(2)]));
+        assert!(!verify_potentials(&matrix, 2, 3, &[1, 2], &[0, 1, 0], &[Some(0), Some(2)]));
+
+        assert!(!verify_potentials(&[i32::MAX], 1, 1, &[-1], &[0], &[Some(0)]));
+    }
+
+
+    #[test]
+    fn test_minimize_binary() {
+        let mut state = 0x874;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 5), (5, 1), (0, 3), (3, 0)] {
+            for _ in 0..50 {
+                let costs = random_matrix(&mut state, height, width, 0, 2);
+                let matrix = costs.iter().map(|&c| c == 1).collect::<Vec<_>>();
+                let assignment = minimize_binary(&matrix, height, width);
+                assert!(is_optimal(&costs, height, width, &assignment));
+                assert_eq!(
+                    total_cost(&costs, width, &assignment),
+                    total_cost(&costs, width, &minimize(&costs, height, width))
+                );
+            }
+        }
+
+        assert_eq!(minimize_binary(&[true; 6], 3, 2), vec![Some(0), Some(1), None]);
+    }
+
+
+    #[test]
+    fn test_solver_update_cost() {
+        let mut state = 0x875;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 4), (5, 1), (8, 8)] {
+            let mut matrix = random_matrix(&mut state, height, width, -5, 30);
+            let mut solver = Solver::new();
+            let initial = solver.minimize_incremental(&matrix, height, width).to_vec();
+            assert!(is_optimal(&matrix, height, width, &initial));
+
+            for _ in 0..200 {
+                let i = (splitmix64(&mut state) % height as u64) as usize;
+                let j = (splitmix64(&mut state) % width as u64) as usize;
+                let cost = random_matrix(&mut state, 1, 1, -5, 30)[0];
+                matrix[width * i + j] = cost;
+
+                let updated = solver.update_cost(i, j, cost).to_vec();
+                assert!(is_optimal(&matrix, height, width, &updated), "{:?}", matrix);
+                assert_eq!(
+                    total_cost(&matrix, width, &updated),
+                    total_cost(&matrix, width, &minimize(&matrix, height, width))
+                );
+            }
+        }
+
+        let matrix = vec![0u8, 7, 7, 0];
+        let mut solver = Solver::new();
+        assert_eq!(solver.minimize_incremental(&matrix, 2, 2), &[Some(0), Some(1)]);
+        assert_eq!(solver.update_cost(0, 0, 255), &[Some(1), Some(0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "minimize_incremental first")]
+    fn test_solver_update_cost_without_state() {
+        let mut solver = Solver::<i32>::new();
+        let _ = solver.update_cost(0, 0, 1);
+    }
+
+
+    #[test]
+    fn test_minimize_bottleneck() {
+        let mut state = 0x876;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 4), (4, 1), (7, 7)] {
+            for _ in 0..50 {
+                let matrix = random_matrix(&mut state, height, width, -20, 50);
+                let (assignment, bottleneck) = minimize_bottleneck(&matrix, height, width);
+                let rows = height.min(width);
+                assert_eq!(assignment.iter().filter(|j| j.is_some()).count(), rows);
+
+                // Attained by some matched edge, and exceeded by none
+                let matched = assignment.iter()
+                    .enumerate()
+                    .filter_map(|(i, &j)| j.map(|j| matrix[width * i + j]))
+                    .collect::<Vec<_>>();
+                assert!(matched.iter().all(|&c| c <= bottleneck));
+                assert!(matched.contains(&bottleneck));
+
+                // No smaller bottleneck is feasible
+                let adjacent = (0..height)
+                    .map(|i| (0..width).filter(|&j| matrix[width * i + j] < bottleneck).collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                assert!(max_matching(&adjacent, width).iter().filter(|j| j.is_some()).count() < rows);
+            }
+        }
+
+        assert_eq!(minimize_bottleneck::<i32>(&[], 2, 0), (vec![None, None], 0));
+    }
+
+
+    #[test]
+    fn test_minimize_source() {
+        struct Slice<'a>(&'a [i64], usize);
+
+        impl<'a> CostSource<i64> for Slice<'a> {
+            fn cost(&self, i: usize, j: usize) -> i64 {
+                self.0[self.1 * i + j]
+            }
+        }
+
+        let mut state = 0x877;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 4), (4, 1), (0, 3)] {
+            let matrix = random_matrix(&mut state, height, width, -10, 50);
+            let expected = minimize(&matrix, height, width);
+            assert_eq!(minimize_source(&Slice(&matrix, width), height, width), expected);
+            assert_eq!(minimize_source(&|i, j| matrix[width * i + j], height, width), expected);
+        }
+    }
+
+
+    #[test]
+    fn test_check_stars() {
+        let stars = arr2(&[
+            [false, true, false],
+            [true, false, false],
+        ]);
+        check_stars(&stars);
+
+        // Duplicated or missing stars that a broken Step 5 could leave behind
+        let broken = vec![
+            arr2(&[[false, true, false], [false, true, false]]),
+            arr2(&[[false, true, true], [true, false, false]]),
+            arr2(&[[false, false, false], [true, false, false]]),
+        ];
+        for stars in broken {
+            assert!(std::panic::catch_unwind(|| check_stars(&stars)).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "invariants")]
+    #[should_panic(expected = "invariant violated")]
+    fn test_invariants_feature() {
+        // Transposed, this would silently assign column 1 to row 1 only
+        let stars = arr2(&[[false, true, false], [false, true, false]]);
+        let _ = ::assignment(&stars, true);
+    }
+
+
+    #[test]
+    fn test_minimize_with_bounds() {
+        let matrix = vec![
+            1, 9,
+            2, 9,
+            3, 9,
+        ];
+        assert_eq!(
+            minimize_with_bounds(&matrix, 3, 2, &[1, 1], &[2, 2]),
+            Ok(vec![Some(0), Some(0), Some(1)])
+        );
+        assert_eq!(
+            minimize_with_bounds(&matrix, 3, 2, &[0, 2], &[1, 3]),
+            Ok(vec![Some(0), Some(1), Some(1)])
+        );
+        assert_eq!(minimize_with_bounds(&matrix, 3, 2, &[2, 2], &[3, 3]), Err(InfeasibleError));
+        assert_eq!(minimize_with_bounds(&matrix, 3, 2, &[2, 0], &[1, 3]), Err(InfeasibleError));
+
+        // Staffing: 4 workers, station 0 needs 1-2, station 1 needs exactly 1,
+        // and station 2 can take at most one; one worker is left over.
+        let matrix = vec![
+            5, 1, 1,
+            5, 2, 9,
+            1, 9, 9,
+            2, 9, 9,
+            9, 9, 9,
+        ];
+        assert_eq!(
+            minimize_with_bounds(&matrix, 5, 3, &[1, 1, 0], &[2, 1, 1]),
+            Ok(vec![Some(2), Some(1), Some(0), Some(0), None])
+        );
+
+        // Without lower bounds, this agrees with the capacities
+        let mut state = 0x879;
+        for _ in 0..30 {
+            let matrix = random_matrix(&mut state, 5, 3, 0, 20);
+            let hi = [2, 1, 3];
+            let bounded = minimize_with_bounds(&matrix, 5, 3, &[0; 3], &hi).unwrap();
+            let capacities = minimize_with_capacities(&matrix, 5, 3, &hi);
+            assert_eq!(total_cost(&matrix, 3, &bounded), total_cost(&matrix, 3, &capacities));
+        }
+    }
+
+
+    #[test]
+    fn test_minimize_detailed() {
+        let mut state = 0x880;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (0, 2), (2, 0)] {
+            let matrix = random_matrix(&mut state, height, width, -10, 50);
+            let detailed = minimize_detailed(&matrix, height, width);
+            let assignment = minimize(&matrix, height, width);
+            assert_eq!(detailed.len(), height);
+            for (i, (detail, j)) in detailed.into_iter().zip(assignment).enumerate() {
+                assert_eq!(detail, j.map(|j| (j, matrix[width * i + j])));
+            }
+        }
+    }
+
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_csv_round_trip() {
+        let input = "82, 83, 69\n77, 37, 49\n11, 69, 5\n8, 9, 98\n";
+        let (matrix, height, width) = ::read_matrix_csv::<i64, _>(input.as_bytes()).unwrap();
+        assert_eq!((height, width), (4, 3));
+        assert_eq!(matrix[..3], [82, 83, 69]);
+
+        let assignment = minimize(&matrix, height, width);
+        let mut out = Vec::new();
+        ::write_assignment_csv(&mut out, &assignment, &matrix, width).unwrap();
+
+        // Reading the output back recovers the assignment and its costs
+        let mut reader = ::csv::Reader::from_reader(&out[..]);
+        let rows = reader.records()
+            .map(|record| {
+                let record = record.unwrap();
+                let j = record[1].parse::<usize>().ok();
+                let cost = record[2].parse::<i64>().ok();
+                assert_eq!(cost, j.map(|j| matrix[width * record[0].parse::<usize>().unwrap() + j]));
+                j
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rows, assignment);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_csv_errors() {
+        match ::read_matrix_csv::<u32, _>("1,2\n3\n".as_bytes()) {
+            Err(::CsvError::Ragged(error)) => {
+                assert_eq!(error, RaggedMatrixError { row: 1, expected: 2, found: 1 })
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match ::read_matrix_csv::<u32, _>("1,2\n3,-4\n".as_bytes()) {
+            Err(::CsvError::Parse { row: 1, col: 1 }) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(::read_matrix_csv::<u32, _>("".as_bytes()).unwrap(), (vec![], 0, 0));
+    }
+
+
+    #[test]
+    fn test_minimize_matrix_mask() {
+        let mut state = 0x883;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 4), (4, 1), (0, 2), (2, 0)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 50);
+            let mask = minimize_matrix_mask(&matrix, height, width);
+            assert_eq!(mask.dim(), (height, width));
+
+            // At most one per row and column, and min(height, width) in total
+            assert!(mask.genrows().into_iter().all(|row| row.iter().filter(|&&v| v).count() <= 1));
+            assert!(mask.gencolumns().into_iter().all(|col| col.iter().filter(|&&v| v).count() <= 1));
+            assert_eq!(mask.iter().filter(|&&v| v).count(), height.min(width));
+
+            let assignment = minimize(&matrix, height, width);
+            for ((i, j), &v) in mask.indexed_iter() {
+                assert_eq!(v, assignment[i] == Some(j));
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_minimize_row_subset() {
+        let mut state = 0x884;
+        let (height, width) = (7, 4);
+        let matrix = random_matrix(&mut state, height, width, 0, 50);
+        let rows = [5, 0, 3];
+
+        let result = minimize_row_subset(&matrix, height, width, &rows);
+        assert_eq!(result.len(), height);
+        assert!([1, 2, 4, 6].iter().all(|&i| result[i].is_none()));
+
+        // Same as solving the rows on their own
+        let sub = rows.iter()
+            .flat_map(|&i| matrix[width * i..width * (i + 1)].to_vec())
+            .collect::<Vec<_>>();
+        let expected = minimize(&sub, rows.len(), width);
+        for (k, &i) in rows.iter().enumerate() {
+            assert_eq!(result[i], expected[k]);
+        }
+
+        // More rows than columns: only some listed rows are assigned
+        let rows = [6, 4, 2, 1, 0];
+        let result = minimize_row_subset(&matrix, height, width, &rows);
+        assert_eq!(result.iter().filter(|j| j.is_some()).count(), width);
+        assert!(result[3].is_none() && result[5].is_none());
+
+        assert_eq!(minimize_row_subset(&matrix, height, width, &[]), vec![None; height]);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn test_minimize_row_subset_duplicate() {
+        let _ = minimize_row_subset(&[1, 2, 3, 4], 2, 2, &[1, 1]);
+    }
+
+
+    #[test]
+    fn test_minimize_top_k_sum() {
+        // Sum of the `k` largest costs
+        fn top_k(mut costs: Vec<i64>, k: usize) -> i64 {
+            costs.sort_by(|a, b| b.cmp(a));
+            costs.iter().take(k).sum()
+        }
+
+        let mut state = 0x886;
+        for &(height, width) in &[(3, 3), (4, 4), (3, 5)] {
+            for k in 0..height + 1 {
+                for _ in 0..20 {
+                    let matrix = random_matrix(&mut state, height, width, 0, 30);
+                    let assignment = minimize_top_k_sum(&matrix, height, width, k);
+                    assert_eq!(assignment.iter().filter(|j| j.is_some()).count(), height);
+
+                    // Smallest top `k` sum over every maximal matching
+                    let mut expected = i64::MAX;
+                    for_each_matching(height, width, true, |a| {
+                        expected = expected.min(top_k(assigned(&matrix, width, a), k));
+                    });
+                    assert_eq!(top_k(assigned(&matrix, width, &assignment), k), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimize_hinted() {
+        let mut state = 0x887;
+        for &(height, width) in &[(5, 5), (3, 6), (6, 3), (1, 4), (0, 2)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 8);
+            let expected = minimize(&matrix, height, width);
+            let optimum = raw_cost(&matrix, width, &expected);
+
+            // An empty hint seeds nothing, so the output is unchanged
+            assert_eq!(minimize_hinted(&matrix, height, width, &vec![None; height]), expected);
+
+            // Invalid hints are ignored
+            let repeated = vec![Some(0); height];
+            let out_of_bounds = vec![Some(width); height];
+            assert_eq!(minimize_hinted(&matrix, height, width, &repeated), expected);
+            assert_eq!(minimize_hinted(&matrix, height, width, &out_of_bounds), expected);
+            assert_eq!(minimize_hinted(&matrix, height, width, &[]), expected);
+
+            // Optimal and arbitrary hints still give an optimal assignment
+            let mut shifted = vec![None; height];
+            for (i, j) in shifted.iter_mut().enumerate().take(width) {
+                *j = Some((i + 1) % width);
+            }
+            for hint in &[expected.clone(), shifted] {
+                let result = minimize_hinted(&matrix, height, width, hint);
+                assert_eq!(raw_cost(&matrix, width, &result), optimum);
+                let mut used = result.iter().filter_map(|&j| j).collect::<Vec<_>>();
+                used.sort_unstable();
+                used.dedup();
+                assert_eq!(used.len(), height.min(width));
+            }
+        }
+    }
+
+    #[test]
+    fn test_maximize_floored() {
         let matrix = vec![
-            250, 400, 350,
-            400, 600, 350,
-            200, 400, 250,
+            9, 8, 4,
+            7, 1, 1,
+            3, 5, 0,
         ];
-        assert_eq!(
-            minimize(&matrix, 3, 3),
-            vec![Some(1), Some(2), Some(0)]
-        );
+
+        // Without a floor, every row is matched
+        assert_eq!(maximize(&matrix, 3, 3), vec![Some(2), Some(0), Some(1)]);
+
+        // Row 2 no longer clears the floor, and row 0 moves over for row 1
+        assert_eq!(maximize_floored(&matrix, 3, 3, 6), vec![Some(1), Some(0), None]);
+        assert_eq!(maximize_floored(&matrix, 3, 3, 8), vec![Some(0), None, None]);
+        assert_eq!(maximize_floored(&matrix, 3, 3, 10), vec![None; 3]);
+
+        // A floor at or below every entry changes nothing
+        assert_eq!(maximize_floored(&matrix, 3, 3, 0), maximize(&matrix, 3, 3));
     }
 
-    // From https://brilliant.org/wiki/hungarian-matching/
     #[test]
-    fn test_party_3x3() {
+    fn test_solve_assignment_views() {
+        let mut state = 0x890;
+        for &(height, width) in &[(4, 4), (3, 5), (5, 3), (0, 3)] {
+            let matrix = random_matrix(&mut state, height, width, -5, 20);
+            let expected = minimize(&matrix, height, width);
+
+            let array = Array2::from_shape_vec((height, width), matrix.clone()).unwrap();
+            assert_eq!(solve_assignment(&array), expected);
+
+            if width > 0 {
+                let rows = matrix.chunks(width).collect::<Vec<_>>();
+                assert_eq!(solve_assignment(&rows[..]), expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 has 1 entries, expected 2")]
+    fn test_solve_assignment_ragged() {
+        let rows: [&[i32]; 2] = [&[1, 2], &[3]];
+        let _ = solve_assignment(&rows[..]);
+    }
+
+    fn check_step_6_stress(max: usize) {
+        for &triangular in &[false, true] {
+            let mut matrix = vec![0; max * max];
+            for i in 0..max {
+                for j in 0..max {
+                    let cost = if triangular && j > i { max * max } else { (i + 1) * (j + 1) };
+                    matrix[index!(max, i, j)] = 1000 * cost as u64;
+                }
+            }
+
+            let assignment = minimize(&matrix, max, max);
+            let iterations = ITERATIONS.with(|n| n.get());
+            assert!(iterations <= max * (2 * max + 1) + 1, "{} iterations for N = {}", iterations, max);
+            assert!(is_optimal(&matrix, max, max, &assignment));
+        }
+    }
+
+    #[test]
+    fn test_step_6_stress() {
+        // Scaled products need about N^2 passes through the main loop, most of
+        // them through [Step 6], and their lower triangles pile every row's
+        // minimum into the first column. Both must stay within the bound above.
+        for &max in &[50, 100] {
+            check_step_6_stress(max);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_step_6_stress_large() {
+        check_step_6_stress(200);
+    }
+
+    #[test]
+    fn test_minimize_dedup_columns() {
+        let mut state = 0x892;
+        for &(height, width) in &[(3, 12), (6, 9), (8, 4), (5, 5), (0, 3), (3, 0)] {
+            // Draw each column from a handful of distinct ones
+            let distinct = random_matrix(&mut state, height, 3, -5, 20);
+            let picks = random_matrix(&mut state, 1, width, 0, 3);
+            let mut matrix = vec![0; height * width];
+            for i in 0..height {
+                for j in 0..width {
+                    matrix[index!(width, i, j)] = distinct[index!(3, i, picks[j] as usize)];
+                }
+            }
+
+            let result = minimize_dedup_columns(&matrix, height, width);
+            let expected = minimize(&matrix, height, width);
+            assert_eq!(raw_cost(&matrix, width, &result), raw_cost(&matrix, width, &expected));
+
+            let mut used = result.iter().filter_map(|&j| j).collect::<Vec<_>>();
+            used.sort_unstable();
+            used.dedup();
+            assert_eq!(used.len(), height.min(width));
+        }
+    }
+
+    #[test]
+    fn test_is_unique_optimum() {
+        // Zeros on the diagonal and large costs elsewhere force the optimum
+        let mut matrix = vec![100; 25];
+        for i in 0..5 {
+            matrix[index!(5, i, i)] = 0;
+        }
+        assert!(is_unique_optimum(&matrix, 5, 5));
+        assert!(is_unique_optimum(&matrix[..15], 3, 5));
+
+        // Every permutation of a constant matrix costs the same
+        assert!(!is_unique_optimum(&[7; 16], 4, 4));
+
+        // Symmetric under swapping the two columns
         let matrix = vec![
-            108, 125, 150,
-            150, 135, 175,
-            122, 148, 250,
+            1, 1,
+            2, 2,
+            5, 5,
         ];
-        assert_eq!(
-            minimize(&matrix, 3, 3),
-            vec![Some(2), Some(1), Some(0)]
-        );
+        assert!(!is_unique_optimum(&matrix, 3, 2));
+
+        // Clamping ties the negative entries
+        assert!(!is_unique_optimum(&[-1, -2, 3], 1, 3));
+        assert!(is_unique_optimum(&[-1, 2, 3], 1, 3));
+        assert!(is_unique_optimum::<i32>(&[], 0, 3));
     }
 
-    // From https://en.wikipedia.org/wiki/Hungarian_algorithm#Matrix_interpretation
     #[test]
-    fn test_wikipedia_4x4() {
+    fn test_minimize_with_sentinel() {
+        let x = -1;
         let matrix = vec![
-            0, 1, 2, 3,
-            4, 5, 6, 0,
-            0, 2, 4, 5,
-            3, 0, 0, 9,
+            5, x, 3, 9,
+            x, 4, x, 2,
+            x, x, x, x,
+            6, 1, x, x,
         ];
+
+        // Clamped to zero, the sentinels look like the cheapest entries
+        let naive = minimize(&matrix, 4, 4);
+        assert!(naive.iter().enumerate().any(|(i, &j)| matrix[index!(4, i, j.unwrap())] == x));
+
+        let result = minimize_with_sentinel(&matrix, 4, 4, x);
+        assert_eq!(result, vec![Some(2), Some(3), None, Some(1)]);
+
+        // Any sentinel value works, including one above the real costs
+        let matrix = matrix.iter().map(|&v| if v == x { 1000 } else { v }).collect::<Vec<_>>();
+        assert_eq!(minimize_with_sentinel(&matrix, 4, 4, 1000), result);
+        assert_eq!(minimize_with_sentinel(&matrix, 4, 4, i32::MAX), minimize_feasible(&matrix, 4, 4));
+    }
+
+    #[test]
+    fn test_permutation() {
+        let p = Permutation::from_assignment(&[Some(2), Some(0), Some(3), Some(1)]).unwrap();
+        let q = Permutation::from_assignment(&[Some(1), Some(2), Some(3), Some(0)]).unwrap();
+        let identity = Permutation::identity(4);
+
+        assert_eq!(p.inverse().as_slice(), &[1, 3, 0, 2]);
+        assert_eq!(p.inverse().inverse(), p);
+        assert_eq!(p.compose(&p.inverse()), identity);
+        assert_eq!(p.inverse().compose(&p), identity);
+        assert_eq!(p.compose(&identity), p);
+
+        let pq = p.compose(&q);
+        assert_eq!(pq.as_slice(), &[0, 1, 2, 3].iter().map(|&i| p.apply(q.apply(i))).collect::<Vec<_>>()[..]);
+        assert_eq!(pq.as_slice(), &[0, 3, 1, 2]);
+        assert_eq!(q.compose(&p).as_slice(), &[3, 1, 0, 2]);
+        assert_eq!(pq.inverse(), q.inverse().compose(&p.inverse()));
+
+        assert_eq!(Permutation::from_assignment(&[Some(0), None]), None);
+        assert_eq!(Permutation::from_assignment(&[Some(0), Some(0)]), None);
+        assert_eq!(Permutation::from_assignment(&[Some(2), Some(0)]), None);
+        assert_eq!(p.to_assignment(), vec![Some(2), Some(0), Some(3), Some(1)]);
+    }
+
+    #[test]
+    fn test_minimize_permutation() {
+        let mut state = 0x896;
+        for n in 0..6 {
+            let matrix = random_matrix(&mut state, n, n, 0, 30);
+            let p = minimize_permutation(&matrix, n).unwrap();
+            assert_eq!(p.to_assignment(), minimize(&matrix, n, n));
+        }
         assert_eq!(
-            minimize(&matrix, 4, 4),
-            vec![Some(1), Some(3), Some(0), Some(2)]
+            minimize_permutation(&[1, 2, 3], 2),
+            Err(DimensionError::LengthMismatch { expected: 4, found: 3 })
         );
     }
 
-    // From https://www.wikihow.com/Use-the-Hungarian-Algorithm
     #[test]
-    fn test_wikihow_5x5() {
-        let matrix = vec![
-            10, 19, 8, 15, 19,
-            10, 18, 7, 17, 19,
-            13, 16, 9, 14, 19,
-            12, 19, 8, 18, 19,
-            14, 17, 10, 19, 19
+    fn test_minimize_expected() {
+        let a = vec![
+            5, 7, 1,
+            1, 7, 7,
+            7, 7, 4,
         ];
-        assert_eq!(
-            minimize(&matrix, 5, 5),
-            vec![Some(0), Some(2), Some(3), Some(4), Some(1)]
-        );
+        let b = vec![
+            1, 2, 1,
+            5, 4, 7,
+            2, 8, 0,
+        ];
+
+        // Each scenario has its own optimum, and the expected one is neither
+        assert_eq!(minimize(&a, 3, 3), vec![Some(2), Some(0), Some(1)]);
+        assert_eq!(minimize(&b, 3, 3), vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(minimize_expected(&[&a, &b], 3, 3), vec![Some(1), Some(0), Some(2)]);
+
+        // Repeating a scenario weighs it more heavily
+        assert_eq!(minimize_expected(&[&a, &a, &a, &b], 3, 3), minimize(&a, 3, 3));
+
+        let big = [i32::MAX; 9];
+        assert_eq!(try_minimize_expected(&[&a, &big], 3, 3), Err(OverflowError { row: 0, col: 0 }));
     }
 
-    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
     #[test]
-    fn test_python_5x5() {
-        let matrix = vec![
-            12,  9, 27, 10, 23,
-             7, 13, 13, 30, 19,
-            25, 18, 26, 11, 26,
-             9, 28, 26, 23, 13,
-            16, 16, 24,  6,  9,
+    fn test_minimize_expected_weighted() {
+        let a = vec![
+            5, 7, 1,
+            1, 7, 7,
+            7, 7, 4,
+        ];
+        let b = vec![
+            1, 2, 1,
+            5, 4, 7,
+            2, 8, 0,
         ];
+
+        // Shifting weight from one scenario to the other changes the optimum
+        let weighted = |wa, wb| minimize_expected_weighted(&[&a[..], &b[..]], &[wa, wb], 3, 3);
+        assert_eq!(weighted(1, 1), minimize_expected(&[&a, &b], 3, 3));
+        assert_eq!(weighted(1, 1), vec![Some(1), Some(0), Some(2)]);
+        assert_eq!(weighted(9, 1), minimize(&a, 3, 3));
+        assert_eq!(weighted(1, 9), minimize(&b, 3, 3));
+        assert_eq!(weighted(0, 1), minimize(&b, 3, 3));
+
+        // Both the products and their sum are checked
+        let big = [i32::MAX / 2; 9];
         assert_eq!(
-            51,
-            minimize(&matrix, 5, 5)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
-                .sum::<u64>()
+            try_minimize_expected_weighted(&[&a, &big], &[1, 3], 3, 3),
+            Err(OverflowError { row: 0, col: 0 })
+        );
+        assert_eq!(
+            try_minimize_expected_weighted(&[&big, &big, &a], &[1, 1, 1], 3, 3),
+            Err(OverflowError { row: 0, col: 0 })
         );
     }
 
-    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
     #[test]
-    fn test_python_10x10() {
+    fn test_minimize_min_churn() {
         let matrix = vec![
-            37, 34, 29, 26, 19,  8,  9, 23, 19, 29,
-             9, 28, 20,  8, 18, 20, 14, 33, 23, 14,
-            15, 26, 12, 28,  6, 17,  9, 13, 21,  7,
-             2,  8, 38, 36, 39,  5, 36,  2, 38, 27,
-            30,  3, 33, 16, 21, 39,  7, 23, 28, 36,
-             7,  5, 19, 22, 36, 36, 24, 19, 30,  2,
-            34, 20, 13, 36, 12, 33,  9, 10, 23,  5,
-             7, 37, 22, 39, 33, 39, 10,  3, 13, 26,
-            21, 25, 23, 39, 31, 37, 32, 33, 38,  1,
-            17, 34, 40, 10, 29, 37, 40,  3, 25,  3,
+            2, 2, 3,
+            2, 2, 3,
+            3, 3, 2,
+            1, 9, 9,
         ];
-        assert_eq!(
-            66,
-            minimize(&matrix, 10, 10)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(10, i, j)]))
-                .sum::<u64>()
-        );
+
+        // Either of rows 0 and 1 can be left out, and the reference picks which
+        let left_out_0 = vec![None, Some(1), Some(2), Some(0)];
+        let left_out_1 = vec![Some(1), None, Some(2), Some(0)];
+        assert_eq!(minimize_min_churn(&matrix, 4, 3, &left_out_0), left_out_0);
+        assert_eq!(minimize_min_churn(&matrix, 4, 3, &left_out_1), left_out_1);
+
+        // Making room for row 3 changes two rows one way, and three the other
+        let before = [Some(0), Some(1), Some(2), None];
+        assert_eq!(minimize_min_churn(&matrix, 4, 3, &before), left_out_0);
+
+        // Compare (cost, churn) against every permutation of random square matrices
+        fn churn(a: &[Option<usize>], b: &[Option<usize>]) -> usize {
+            a.iter().zip(b).filter(|&(a, b)| a != b).count()
+        }
+
+        let mut state = 0x899;
+        for _ in 0..50 {
+            let matrix = random_matrix(&mut state, 4, 4, 0, 3);
+            let reference = random_matrix(&mut state, 1, 4, 0, 5)
+                .into_iter()
+                .map(|j| if j < 4 { Some(j as usize) } else { None })
+                .collect::<Vec<_>>();
+
+            let result = minimize_min_churn(&matrix, 4, 4, &reference);
+            let mut expected = (i64::MAX, usize::MAX);
+            for_each_matching(4, 4, true, |a| {
+                expected = expected.min((raw_cost(&matrix, 4, a), churn(a, &reference)));
+            });
+            assert_eq!((raw_cost(&matrix, 4, &result), churn(&result, &reference)), expected);
+        }
     }
 
-    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
     #[test]
-    fn test_python_20x20() {
+    fn test_lower_bound() {
+        let mut state = 0x901;
+        for &(height, width) in &[(1, 1), (1, 5), (5, 1), (4, 4), (3, 6), (6, 3), (7, 7), (0, 3), (3, 0)] {
+            for _ in 0..20 {
+                let matrix = random_matrix(&mut state, height, width, -5, 30);
+                let optimum = minimize_cost_only(&matrix, height, width);
+                assert!(lower_bound(&matrix, height, width) <= optimum);
+            }
+        }
+
+        // Exact on a single row or column, where the optimum is the cheapest entry
+        let matrix = vec![4, 2, 7];
+        assert_eq!(lower_bound(&matrix, 1, 3), 2);
+        assert_eq!(lower_bound(&matrix, 3, 1), 2);
+    }
+
+    #[test]
+    fn test_minimize_blocks() {
+        let mut state = 0x902;
+        let sizes = [(3, 3), (1, 1), (4, 4), (2, 2)];
+        let blocks = sizes.iter()
+            .map(|&(height, width)| (random_matrix(&mut state, height, width, 0, 50), height, width))
+            .collect::<Vec<_>>();
+
+        // Combine the blocks along the diagonal, with a prohibitive cost elsewhere
+        let n = sizes.iter().map(|&(height, _)| height).sum::<usize>();
+        let mut combined = vec![10_000; n * n];
+        let mut offset = 0;
+        for &(ref matrix, height, width) in &blocks {
+            for i in 0..height {
+                for j in 0..width {
+                    combined[index!(n, offset + i, offset + j)] = matrix[index!(width, i, j)];
+                }
+            }
+            offset += height;
+        }
+
+        let result = minimize_blocks(&blocks);
+        assert_eq!(result.len(), blocks.len());
+
+        let mut total = 0;
+        for (assignment, &(ref matrix, height, width)) in result.iter().zip(&blocks) {
+            assert_eq!(assignment, &minimize(matrix, height, width));
+            total += raw_cost(matrix, width, assignment);
+        }
+        assert_eq!(total, raw_cost(&combined, n, &minimize(&combined, n, n)));
+
+        // Rectangular and empty blocks are solved like any other matrix
+        let blocks = vec![(vec![3, 1, 2, 2, 5, 1], 2, 3), (vec![4, 1, 7], 3, 1), (vec![], 0, 2)];
+        assert_eq!(minimize_blocks(&blocks), vec![vec![Some(1), Some(2)], vec![None, Some(0), None], vec![]]);
+    }
+
+    #[test]
+    fn test_maximize_cardinality_under_budget() {
         let matrix = vec![
-            5,  4,  3,  9,  8,  9,  3,  5,  6,  9,  4, 10,  3,  5,  6,  6,  1,  8, 10,  2,
-            10, 9,  9,  2,  8,  3,  9,  9, 10,  1,  7, 10,  8,  4,  2,  1,  4,  8,  4,  8,
-            10, 4,  4,  3,  1,  3,  5, 10,  6,  8,  6,  8,  4, 10,  7,  2,  4,  5,  1,  8,
-            2,  1,  4,  2,  3,  9,  3,  4,  7,  3,  4,  1,  3,  2,  9,  8,  6,  5,  7,  8,
-            3,  4,  4,  1,  4, 10,  1,  2,  6,  4,  5, 10,  2,  2,  3,  9, 10,  9,  9, 10,
-            1, 10,  1,  8,  1,  3,  1,  7,  1,  1,  2,  1,  2,  6,  3,  3,  4,  4,  8,  6,
-            1,  8,  7, 10, 10,  3,  4,  6,  1,  6,  6,  4,  9,  6,  9,  6,  4,  5,  4,  7,
-            8, 10,  3,  9,  4,  9,  3,  3,  4,  6,  4,  2,  6,  7,  7,  4,  4,  3,  4,  7,
-            1,  3,  8,  2,  6,  9,  2,  7,  4,  8, 10,  8, 10,  5,  1,  3, 10, 10,  2,  9,
-            2,  4,  1,  9,  2,  9,  7,  8,  2,  1,  4, 10,  5,  2,  7,  6,  5,  7,  2,  6,
-            4,  5,  1,  4,  2,  3,  3,  4,  1,  8,  8,  2,  6,  9,  5,  9,  6,  3,  9,  3,
-            3,  1,  1,  8,  6,  8,  8,  7,  9,  3,  2,  1,  8,  2,  4,  7,  3,  1,  2,  4,
-            5,  9,  8,  6, 10,  4, 10,  3,  4, 10, 10, 10,  1,  7,  8,  8,  7,  7,  8,  8,
-            1,  4,  6,  1,  6,  1,  2, 10,  5, 10,  2,  6,  2,  4,  5,  5,  3,  5,  1,  5,
-            5,  6,  9, 10,  6,  6, 10,  6,  4,  1,  5,  3,  9,  5,  2, 10,  9,  9,  5,  1,
-            10, 9,  4,  6,  9,  5,  3,  7, 10,  1,  6,  8,  1,  1, 10,  9,  5,  7,  7,  5,
-            2,  6,  6,  6,  6,  2,  9,  4,  7,  5,  3,  2, 10,  3,  4,  5, 10,  9,  1,  7,
-            5,  2,  4,  9,  8,  4,  8,  2,  4,  1,  3,  7,  6,  8,  1,  6,  8,  8, 10, 10,
-            9,  6,  3,  1,  8,  5,  7,  8,  7,  2,  1,  8,  2,  8,  3,  7,  4,  8,  7,  7,
-            8,  4,  4,  9,  7, 10,  6,  2,  1,  5,  8,  5,  1,  1,  1,  9,  1,  3,  5,  3,
+            2, 3, 9,
+            3, 2, 9,
+            9, 9, 7,
+            4, 9, 9,
         ];
-        assert_eq!(
-            22,
-            minimize(&matrix, 20, 20)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(20, i, j)]))
-                .sum::<u64>()
-        );
+
+        // Without a binding budget, this is a plain minimum cost assignment
+        let full = maximize_cardinality_under_budget(&matrix, 4, 3, 100);
+        assert_eq!(full, minimize(&matrix, 4, 3));
+        assert_eq!(raw_cost(&matrix, 3, &full), 11);
+
+        // Row 2's match is the most expensive, and the first to go
+        assert_eq!(maximize_cardinality_under_budget(&matrix, 4, 3, 10), vec![Some(0), Some(1), None, None]);
+        assert_eq!(maximize_cardinality_under_budget(&matrix, 4, 3, 3), vec![Some(0), None, None, None]);
+        assert_eq!(maximize_cardinality_under_budget(&matrix, 4, 3, 1), vec![None; 4]);
+        assert_eq!(maximize_cardinality_under_budget(&matrix, 4, 3, -1), vec![None; 4]);
+
+        // Compare the size and cost against every partial matching
+        let mut state = 0x903;
+        for &(height, width) in &[(3, 3), (4, 3), (3, 5)] {
+            for budget in 0..25 {
+                let matrix = random_matrix(&mut state, height, width, 0, 10);
+                let result = maximize_cardinality_under_budget(&matrix, height, width, budget);
+                let size = result.iter().filter(|j| j.is_some()).count();
+
+                // Largest matching within the budget, then cheapest
+                let mut expected = (0, 0);
+                for_each_matching(height, width, false, |a| {
+                    let (size, cost) = (a.iter().filter(|j| j.is_some()).count(), raw_cost(&matrix, width, a));
+                    if cost <= budget && (size, -cost) > (expected.0, -expected.1) { expected = (size, cost) }
+                });
+                assert_eq!((size, raw_cost(&matrix, width, &result)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimize_fixed() {
+        fn check<const N: usize>(state: &mut u64) {
+            for _ in 0..10 {
+                let flat = random_matrix(state, N, N, -10, 40);
+                let mut matrix = [[0; N]; N];
+                for i in 0..N {
+                    for j in 0..N {
+                        matrix[i][j] = flat[index!(N, i, j)];
+                    }
+                }
+
+                let result = minimize_fixed(&matrix).iter().map(|&j| Some(j)).collect::<Vec<_>>();
+                let expected = minimize(&flat, N, N);
+                let clamped = flat.iter().map(|&v| v.max(0)).collect::<Vec<_>>();
+                assert_eq!(raw_cost(&clamped, N, &result), raw_cost(&clamped, N, &expected));
+
+                let mut used = result.clone();
+                used.sort_unstable();
+                used.dedup();
+                assert_eq!(used.len(), N);
+            }
+        }
+
+        let mut state = 0x904;
+        check::<0>(&mut state);
+        check::<1>(&mut state);
+        check::<2>(&mut state);
+        check::<3>(&mut state);
+        check::<4>(&mut state);
+        check::<5>(&mut state);
+        check::<8>(&mut state);
+        check::<13>(&mut state);
+        check::<16>(&mut state);
+        check::<64>(&mut state);
+
+        // The worst case of `test_worst_case_iterations`, at the largest supported size
+        let mut matrix = [[0u64; 64]; 64];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cost) in row.iter_mut().enumerate() {
+                *cost = ((i + 1) * (j + 1)) as u64;
+            }
+        }
+        let expected = (0..64).rev().collect::<Vec<_>>();
+        assert_eq!(&minimize_fixed(&matrix)[..], &expected[..]);
     }
 
-    // From https://stackoverflow.com/questions/37687045/hungarian-algorithm-dead-end
     #[test]
-    fn test_stack_overflow_4x4_zeros() {
+    fn test_minimize_utilization() {
         let matrix = vec![
-            0, 0, 0, 0,
-            0, 0, 0, 0,
-            0, 0, 1, 2,
-            0, 0, 3, 4,
+            7, 3, 9, 1, 4, 6,
+            2, 8, 5, 9, 1, 3,
+            6, 2, 7, 4, 8, 2,
         ];
-        assert_eq!(
-            minimize(&matrix, 4, 4),
-            vec![Some(3), Some(2), Some(1), Some(0)]
-        );
+
+        let report = minimize_utilization(&matrix, 3, 6);
+        let assignment = minimize(&matrix, 3, 6);
+        assert_eq!(report.edges, assignment_edges(&matrix, 3, 6, &assignment));
+        assert_eq!(report.edges, vec![(0, 3, 1), (1, 4, 1), (2, 1, 2)]);
+        assert_eq!(report.unused, vec![
+            UnusedColumn { column: 0, min_cost: 2, cheapest_row: 1 },
+            UnusedColumn { column: 2, min_cost: 5, cheapest_row: 1 },
+            UnusedColumn { column: 5, min_cost: 2, cheapest_row: 2 },
+        ]);
+
+        // Every column is used when the matrix isn't wide
+        assert!(minimize_utilization(&matrix, 6, 3).unused.is_empty());
+        assert_eq!(minimize_utilization::<i32>(&[], 0, 4), UtilizationReport { edges: vec![], unused: vec![] });
     }
 
-    // From https://stackoverflow.com/questions/46803600/hungarian-algorithm-wikipedia-method-doesnt-work-for-this-example
     #[test]
-    fn test_stack_overflow_4x4() {
+    fn test_all_optima() {
         let matrix = vec![
-            35,  0,  0,  0,
-            0 , 30,  0,  5,
-            55,  5,  0, 10,
-            0 , 45, 30, 45,
+            3, 5,
+            5, 3,
         ];
-        assert_eq!(
-            5,
-            minimize(&matrix, 4, 4)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
-                .sum::<u64>()
-        );
-        assert_eq!(
-            minimize(&matrix, 4, 4),
-            vec![Some(1), Some(3), Some(2), Some(0)]
-        );
-    }
+        assert_eq!(all_optima(&matrix, 2, 2), vec![vec![Some(0), Some(1)]]);
 
-    // From https://stackoverflow.com/questions/17419595/hungarian-kuhn-munkres-algorithm-oddity
-    #[test]
-    fn test_stack_overflow_5x5() {
+        // Symmetric under swapping the columns
         let matrix = vec![
-            0, 7, 0, 0, 0,
-            0, 8, 0, 0, 6,
-            5, 0, 7, 3, 4,
-            5, 0, 5, 9, 3,
-            0, 4, 0, 0, 9,
+            3, 3,
+            5, 5,
         ];
-        assert_eq!(
-            3,
-            minimize(&matrix, 5, 5)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
-                .sum::<u64>()
-        );
-        assert_eq!(
-            minimize(&matrix, 5, 5),
-            vec![Some(4), Some(2), Some(3), Some(1), Some(0)]
-        );
+        assert_eq!(all_optima(&matrix, 2, 2), vec![vec![Some(0), Some(1)], vec![Some(1), Some(0)]]);
+
+        // Every permutation of a constant matrix, and no duplicates from padding
+        assert_eq!(all_optima(&[1; 64], 8, 8).len(), 40320);
+        assert_eq!(all_optima(&[1; 6], 3, 2).len(), 6);
+        assert_eq!(all_optima(&[1; 6], 2, 3).len(), 6);
+
+        // Compare against the k best matchings of equal cost
+        let mut state = 0x906;
+        for &(height, width) in &[(3, 3), (4, 4), (3, 5), (5, 3)] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut state, height, width, 0, 3);
+                let optima = all_optima(&matrix, height, width);
+                let best = minimize_k_best(&matrix, height, width, 200);
+                let mut expected = best.iter()
+                    .filter(|&(_, cost)| *cost == best[0].1)
+                    .map(|(assignment, _)| assignment.clone())
+                    .collect::<Vec<_>>();
+                expected.sort_unstable();
+                assert_eq!(optima, expected);
+            }
+        }
     }
 
-    // From https://stackoverflow.com/questions/37687045/hungarian-algorithm-dead-end
     #[test]
-    fn test_stack_overflow_6x6() {
-        let matrix = vec![
-            2, 1, 0, 0, 0, 3,
-            2, 0, 4, 5, 2, 7,
-            0, 7, 0, 0, 0, 5,
-            3, 2, 3, 1, 2, 0,
-            0, 0, 6, 3, 3, 5,
-            3, 4, 5, 2, 0, 3,
-        ];
-        assert_eq!(
-            minimize(&matrix, 6, 6),
-            vec![Some(3), Some(1), Some(2), Some(5), Some(0), Some(4)]
-        );
+    #[should_panic(expected = "at most 8 rows and columns")]
+    fn test_all_optima_too_large() {
+        let _ = all_optima(&[0; 9], 1, 9);
     }
 
-    // From https://stackoverflow.com/questions/26893961/cannot-solve-hungarian-algorithm
     #[test]
-    fn test_stack_overflow_14x11() {
-        let matrix = vec![
-             0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
-            53, 207, 256, 207, 231, 348, 348, 348, 231, 244, 244,
-           240,  33,  67,  33,  56, 133, 133, 133,  56,  33,  33,
-           460, 107, 200, 107, 122, 324, 324, 324, 122,  33,  33,
-           167, 340, 396, 340, 422, 567, 567, 567, 422, 442, 442,
-           167, 367, 307, 367, 433, 336, 336, 336, 433, 158, 158,
-           160,  20,  37,  20,  31,  70,  70,  70,  31,  22,  22,
-           200, 307, 393, 307, 222, 364, 364, 364, 222, 286, 286,
-           33 , 153, 152, 153, 228, 252, 252, 252, 228,  78,  78,
-           93 , 140, 185, 140,  58, 118, 118, 118,  58,  44,  44,
-           0  ,   7,  22,   7,  19,  58,  58,  58,  19,   0,   0,
-           67 , 153, 241, 153, 128, 297, 297, 297, 128,  39,  39,
-           73 , 253, 389, 253, 253, 539, 539, 539, 253,  36,  36,
-           173, 267, 270, 267, 322, 352, 352, 352, 322, 231, 231,
-        ];
-        assert_eq!(
-            828,
-            minimize(&matrix, 14, 11)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(11, i, j)]))
-                .sum::<u64>()
-        );
+    fn test_online_solver() {
+        let mut state = 0x907;
+        for &width in &[1, 3, 5] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut state, 8, width, 0, 10);
+                let mut solver = OnlineSolver::new(width);
+                for height in 1..=8 {
+                    let rows = &matrix[..height * width];
+                    let assignment = solver.push_row(&rows[width * (height - 1)..]).to_vec();
+                    assert_eq!(assignment.len(), height);
+                    assert_eq!(assignment.iter().filter(|j| j.is_some()).count(), height.min(width));
+                    assert_eq!(
+                        raw_cost(rows, width, &assignment),
+                        raw_cost(rows, width, &minimize(rows, height, width)),
+                    );
+                }
+                assert_eq!(solver.assignment().len(), 8);
+            }
+        }
+
+        // Unsigned costs never underflow the reduced costs
+        let mut solver = OnlineSolver::<u8>::new(2);
+        assert_eq!(solver.push_row(&[5, 0]), &[Some(1)]);
+        assert_eq!(solver.push_row(&[9, 0]), &[Some(0), Some(1)]);
+        assert_eq!(solver.push_row(&[0, 9]), &[None, Some(1), Some(0)]);
     }
 
-    // From https://github.com/bmc/munkres/blob/master/munkres.py
     #[test]
-    fn test_rectangle_3x4() {
-        let matrix = vec![
-            400, 150, 400, 1,
-            400, 450, 600, 2,
-            300, 225, 300, 3,
-        ];
-        assert_eq!(
-            452,
-            minimize(&matrix, 3, 4)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
-                .sum::<u64>()
-        );
-        assert_eq!(
-            minimize(&matrix, 3, 4),
-            vec![Some(1), Some(3), Some(0)]
-        );
+    #[should_panic(expected = "expected 3 costs, got 2")]
+    fn test_online_solver_wrong_width() {
+        let _ = OnlineSolver::new(3).push_row(&[1, 2]);
     }
 
-    // Modified from http://www.hungarianalgorithm.com/examplehungarianalgorithm.php
     #[test]
-    fn test_rectangle_4x5() {
+    fn test_minimize_prefer() {
         let matrix = vec![
-            82, 83, 69, 92, 100,
-            77, 37, 49, 92, 195,
-            11, 69,  5, 86,  93,
-             8,  9, 98, 23, 106,
+            4, 1,
+            2, 5,
+            3, 3,
         ];
-        assert_eq!(
-            140,
-            minimize(&matrix, 4, 5)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(5, i, j)]))
-                .sum::<u64>()
-        );
-        assert_eq!(
-            minimize(&matrix, 4, 5),
-            vec![Some(2), Some(1), Some(0), Some(3)]
-        );
+
+        // Every column is matched, and row 2 is left over
+        assert_eq!(minimize_prefer(&matrix, 3, 2, Axis::Rows), vec![Some(1), Some(0), None]);
+        assert_eq!(minimize_prefer(&matrix, 3, 2, Axis::Columns), vec![Some(1), Some(0)]);
+
+        let mut state = 0x908;
+        for &(height, width) in &[(3, 5), (5, 3), (4, 4)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 10);
+            let rows = minimize_prefer(&matrix, height, width, Axis::Rows);
+            let columns = minimize_prefer(&matrix, height, width, Axis::Columns);
+            assert_eq!(rows, minimize(&matrix, height, width));
+            assert_eq!(columns.len(), width);
+            assert_eq!(transpose_assignment(&columns, width, height), rows);
+        }
     }
 
-    // From https://github.com/bmc/munkres/blob/master/test/test_munkres.py
     #[test]
-    fn test_rectangle_5x4() {
-        let matrix = vec![
-            34, 26, 17, 12,
-            43, 43, 36, 10,
-            97, 47, 66, 34,
-            52, 42, 19, 36,
-            15, 93, 55, 80
-        ];
-        assert_eq!(
-            70,
-            minimize(&matrix, 5, 4)
-                .iter()
-                .enumerate()
-                .filter_map(|(i, &v)| v.map(|j| matrix[index!(4, i, j)]))
-                .sum::<u64>()
-        );
-        assert_eq!(
-            minimize(&matrix, 5, 4),
-            vec![Some(1), Some(3), None, Some(2), Some(0)]
-        );
+    #[cfg(feature = "simd")]
+    fn test_lane_min() {
+        let mut state = 0x909;
+        for len in 1..40 {
+            let row = random_matrix(&mut state, 1, len, -50, 50);
+            assert_eq!(::lane_min(&row), *row.iter().min().unwrap());
+            let row = row.iter().map(|&v| (v + 50) as u8).collect::<Vec<_>>();
+            assert_eq!(::lane_min(&row), *row.iter().min().unwrap());
+        }
+
+        let mut state = 0x909;
+        for &(height, width) in &[(5, 5), (8, 20), (17, 33)] {
+            let matrix = random_matrix(&mut state, height, width, 0, 100);
+            let assignment = minimize(&matrix, height, width);
+            assert!(is_optimal(&matrix, height, width, &assignment));
+        }
     }
 
     #[test]
-    fn test_rectangle_nx1() {
-        let max = 100;
-        for n in 1..max {
-            let matrix = (0..n as u64).rev().collect::<Vec<_>>();
-            let mut expected = vec![None; n];
-            expected[n - 1] = Some(0);
-            assert_eq!(minimize(&matrix, n, 1), expected);
+    fn test_equality_subgraph() {
+        assert!(equality_subgraph::<i32>(&[], 0, 3).is_empty());
+
+        let mut state = 0x910;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 5)] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut state, height, width, 0, 4);
+                let edges = equality_subgraph(&matrix, height, width);
+                let within = |assignment: &[Option<usize>]| assignment.iter()
+                    .enumerate()
+                    .filter_map(|(i, &j)| j.map(|j| (i, j)))
+                    .all(|edge| edges.binary_search(&edge).is_ok());
+
+                // The chosen matching, and every other optimum, is in the subgraph
+                assert!(within(&minimize(&matrix, height, width)));
+                for optimum in all_optima(&matrix, height, width) {
+                    assert!(within(&optimum));
+                }
+            }
         }
     }
 
     #[test]
-    fn test_rectangle_1xn() {
-        let max = 100;
-        for n in 1..max {
-            let matrix = (0..n as u64).rev().collect::<Vec<_>>();
-            let expected = vec![Some(n - 1)];
-            assert_eq!(minimize(&matrix, 1, n), expected);
+    fn test_solver_minimize_prereduced() {
+        let mut solver = Solver::new();
+        let mut state = 0x911;
+        for &(height, width) in &[(1, 4), (4, 1), (5, 5), (3, 7), (7, 3)] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut state, height, width, -3, 20);
+                let row_mins = matrix.chunks(width)
+                    .map(|row| *row.iter().min().unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    solver.minimize_prereduced(&matrix, height, width, &row_mins),
+                    solver.minimize(&matrix, height, width),
+                );
+            }
         }
     }
 
     #[test]
-    fn test_stress() {
-        for max in 1..100 {
-            let mut matrix = vec![0; max * max];
-            let mut n: u64 = 0;
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must hold the minimum of each row")]
+    fn test_solver_minimize_prereduced_wrong_minima() {
+        let _ = Solver::new().minimize_prereduced(&[1, 2, 3, 4], 2, 2, &[1, 4]);
+    }
 
-            for i in 0..max {
-                for j in 0..max {
-                    matrix[index!(max, i, j)] = n;
-                    n += 1;
+    #[test]
+    fn test_maximize_product_f64_brute_force() {
+        let mut state = 0x913;
+        for &(height, width) in &[(3, 3), (4, 4), (2, 5), (5, 3)] {
+            for _ in 0..50 {
+                let matrix = random_matrix(&mut state, height, width, 1, 21)
+                    .into_iter()
+                    .map(|c| c as f64 / 20.0)
+                    .collect::<Vec<_>>();
+                let assignment = maximize_product_f64(&matrix, height, width, 1e-12);
+                let product = assigned(&matrix, width, &assignment).iter().product::<f64>();
+
+                // Largest product over every maximal matching
+                let mut expected = 0.0;
+                for_each_matching(height, width, true, |a| {
+                    expected = f64::max(expected, assigned(&matrix, width, a).iter().product());
+                });
+                assert_eq!(assignment.iter().filter(|j| j.is_some()).count(), height.min(width));
+                assert!((product - expected).abs() <= 1e-9 * expected, "{} != {}", product, expected);
+            }
+        }
+
+        // Zero likelihoods are avoided whenever a maximal matching can
+        let matrix = vec![
+            0.0, 0.0, 0.1,
+            0.0, 0.0, 0.9,
+            0.3, 0.2, 0.0,
+        ];
+        assert_eq!(maximize_product_f64(&matrix, 3, 3, 1e-12)[2], Some(0));
+        assert_eq!(maximize_product_f64(&matrix, 3, 3, 1e-12)[1], Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be finite and non-negative")]
+    fn test_maximize_product_f64_negative() {
+        let _ = maximize_product_f64(&[0.5, -0.5], 1, 2, 1e-12);
+    }
+
+    #[test]
+    fn test_feasible_matching() {
+        // Rows 0, 1 and 2 can only use columns 0 and 1, so Hall's condition fails
+        let matrix = vec![
+            1, 1, -1, -1,
+            1, -1, -1, -1,
+            -1, 1, -1, -1,
+            1, 1, 1, 1,
+        ];
+        assert_eq!(feasible_matching(&matrix, 4, 4, -1), None);
+        assert_eq!(feasible_matching(&matrix, 2, 4, -1), Some(vec![Some(1), Some(0)]));
+        assert_eq!(feasible_matching::<i32>(&[], 0, 3, -1), Some(vec![]));
+        assert_eq!(feasible_matching(&[-1; 3], 3, 1, -1), None);
+
+        // Agrees with the number of rows the min-cost solver can assign
+        let mut state = 0x914;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3)] {
+            for _ in 0..20 {
+                let matrix = random_matrix(&mut state, height, width, 0, 3);
+                let assigned = minimize_with_sentinel(&matrix, height, width, 0).iter()
+                    .filter(|j| j.is_some())
+                    .count();
+                match feasible_matching(&matrix, height, width, 0) {
+                    Some(assignment) => {
+                        assert_eq!(assigned, height.min(width));
+                        let mut columns = assignment.iter().filter_map(|&j| j).collect::<Vec<_>>();
+                        columns.sort_unstable();
+                        columns.dedup();
+                        assert_eq!(columns.len(), assigned);
+                        assert!(assignment.iter()
+                            .enumerate()
+                            .filter_map(|(i, &j)| j.map(|j| matrix[width * i + j]))
+                            .all(|cost| cost != 0));
+                    }
+                    None => assert!(assigned < height.min(width)),
                 }
             }
+        }
+    }
 
-            let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
-            assert_eq!(minimize(&matrix, max, max), expected);
+    #[test]
+    #[should_panic(expected = "too large for squared distances")]
+    fn test_minimize_variance_overflow() {
+        let matrix = vec![u64::MAX / 2, 0, 0, u64::MAX / 2];
+        let _ = minimize_variance(&matrix, 2, 2);
+    }
+
+    #[test]
+    fn test_minimize_variance_brute_force() {
+        // `k^2` times the variance of a matching of `k` costs, as an integer
+        fn spread(costs: &[i64]) -> i64 {
+            let k = costs.len() as i64;
+            let sum = costs.iter().sum::<i64>();
+            k * costs.iter().map(|c| c * c).sum::<i64>() - sum * sum
+        }
+
+        let mut state = 0x916;
+        for &(height, width) in &[(2, 2), (3, 3), (4, 4), (2, 4), (4, 3)] {
+            for _ in 0..20 {
+                let matrix = random_matrix(&mut state, height, width, 0, 12);
+                let assignment = minimize_variance(&matrix, height, width);
+                let costs = assigned(&matrix, width, &assignment);
+                assert_eq!(costs.len(), height.min(width));
+
+                // Least spread over every maximal matching
+                let mut expected = i64::MAX;
+                for_each_matching(height, width, true, |a| {
+                    expected = expected.min(spread(&assigned(&matrix, width, a)));
+                });
+                assert_eq!(spread(&costs), expected);
+            }
         }
+
+        assert_eq!(minimize_variance(&[3u8, 1, 2], 1, 3), vec![Some(1)]);
+        assert_eq!(minimize_variance::<i32>(&[], 0, 2), vec![]);
     }
 
     #[test]
-    fn test_worst_case() {
-        for max in 1..50 {
-            let mut matrix = vec![0; max * max];
+    fn test_minimize_bounded_by() {
+        let mut state = 0x917;
+        for &(height, width) in &[(4, 4), (3, 6), (6, 3), (1, 4)] {
+            for _ in 0..20 {
+                let matrix = random_matrix(&mut state, height, width, 0, 20);
+                let optimum = raw_cost(&matrix, width, &minimize(&matrix, height, width));
 
-            for i in 0..max {
-                for j in 0..max {
-                    matrix[index!(max, i, j)] = ((i + 1)*(j + 1)) as u64;
+                // A loose or exact bound gives the full solution
+                for &bound in &[optimum, optimum + 1, i64::MAX] {
+                    let (assignment, cost) = minimize_bounded_by(&matrix, height, width, bound).unwrap();
+                    assert_eq!(cost, optimum);
+                    assert_eq!(raw_cost(&matrix, width, &assignment), optimum);
                 }
+
+                // Any tighter bound gives up
+                assert_eq!(minimize_bounded_by(&matrix, height, width, optimum - 1), None);
             }
+        }
 
-            let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
-            assert_eq!(minimize(&matrix, max, max), expected);
+        assert_eq!(minimize_bounded_by::<i32>(&[], 0, 3, 0), Some((vec![], 0)));
+        assert_eq!(minimize_bounded_by::<i32>(&[], 0, 3, -1), None);
+    }
+
+    #[test]
+    fn test_minimize_with_max_cost() {
+        let matrix = vec![
+            3, 1, 7,
+            2, 8, 9,
+            6, 4, 5,
+        ];
+
+        // Feasible: the optimum already respects the cap
+        assert_eq!(minimize_with_max_cost(&matrix, 3, 3, 9), Ok(minimize(&matrix, 3, 3)));
+        assert_eq!(minimize_with_max_cost(&matrix, 3, 3, 5), Ok(vec![Some(1), Some(0), Some(2)]));
+
+        // Infeasible: rows 0 and 1 both need column 0 under a cap of 2,
+        // where the gate instead leaves a row unassigned
+        assert_eq!(minimize_with_max_cost(&matrix, 3, 3, 2), Err(InfeasibleError));
+        let gated = AssignmentProblem::new(&matrix, 3, 3).gate(2).solve().unwrap();
+        assert_eq!(gated.rows().iter().filter(|j| j.is_some()).count(), 2);
+
+        // Rectangular: only the shorter side has to be assigned
+        assert_eq!(minimize_with_max_cost(&matrix[..6], 2, 3, 3), Ok(vec![Some(1), Some(0)]));
+        assert_eq!(minimize_with_max_cost(&matrix[..6], 2, 3, 1), Err(InfeasibleError));
+    }
+
+    #[test]
+    fn test_minimize_in_place() {
+        let mut state = 0x919;
+        for &(height, width) in &[(1, 5), (5, 1), (4, 4), (3, 7), (7, 3), (20, 20)] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut state, height, width, 0, 50);
+                let mut reduced = matrix.clone();
+                let assignment = minimize_in_place(&mut reduced, height, width);
+                assert_eq!(assignment, minimize(&matrix, height, width));
+                assert_eq!(raw_cost(&reduced, width, &assignment), 0);
+                assert!(reduced.iter().all(|&cost| cost >= 0));
+            }
         }
+
+        // Negative entries keep their actual values, even with a single row
+        let mut matrix = vec![-1, -5];
+        assert_eq!(minimize_in_place(&mut matrix, 1, 2), vec![Some(1)]);
+        assert_eq!(matrix, vec![4, 0]);
+
+        let mut matrix = vec![-1, -5, 3];
+        assert_eq!(minimize_in_place(&mut matrix, 3, 1), vec![None, Some(0), None]);
+        assert_eq!(minimize_in_place(&mut [0i64; 0], 3, 0), vec![None; 3]);
     }
 
     #[test]
-    fn test_large() {
-        let max = 1000;
-        let mut matrix = vec![0; max * max];
-        let mut n: u64 = 0;
+    fn test_minimize_with_potentials() {
+        let mut state = 0x920;
+        for &(height, width) in &[(4, 4), (2, 5), (5, 2), (3, 4), (6, 3), (1, 3), (3, 1)] {
+            for _ in 0..20 {
+                let matrix = random_matrix(&mut state, height, width, -3, 20);
+                let (assignment, row_pot, col_pot) = minimize_with_potentials(&matrix, height, width);
+                assert_eq!((assignment.len(), row_pot.len(), col_pot.len()), (height, height, width));
+                assert!(is_optimal(&matrix, height, width, &assignment));
+                assert!(verify_potentials(&matrix, height, width, &row_pot, &col_pot, &assignment));
 
-        for i in 0..max {
-            for j in 0..max {
-                matrix[index!(max, i, j)] = n;
-                n += 1;
+                // Complementary slackness, in the caller's indices
+                for i in 0..height {
+                    for j in 0..width {
+                        let reduced = matrix[index!(width, i, j)].max(0) - row_pot[i] - col_pot[j];
+                        assert!(reduced >= 0);
+                        if assignment[i] == Some(j) { assert_eq!(reduced, 0) }
+                    }
+                }
+                if height < width {
+                    for j in (0..width).filter(|&j| !assignment.contains(&Some(j))) {
+                        assert_eq!(col_pot[j], 0);
+                    }
+                }
+                if width < height {
+                    for i in (0..height).filter(|&i| assignment[i].is_none()) {
+                        assert_eq!(row_pot[i], 0);
+                    }
+                }
             }
         }
+    }
 
-        let expected = (0..max).map(|i| Some(i)).rev().collect::<Vec<_>>();
-        assert_eq!(minimize(&matrix, max, max), expected);
+    #[test]
+    fn test_minimize_with_regret() {
+        let matrix = vec![
+            4, 1, 7,
+            2, 0, 5,
+            3, 2, 9,
+        ];
+
+        // The optimum 1 + 5 + 3 = 9 gives row 0 its first choice, while
+        // row 1 pays 5 instead of 0, and row 2 pays 3 instead of 2
+        assert_eq!(minimize_with_regret(&matrix, 3, 3), vec![Some((1, 0)), Some((2, 5)), Some((0, 1))]);
+
+        // Unassigned rows have no regret, and negative costs are clamped first
+        let matrix = vec![
+            -5, 1,
+            -2, 7,
+            1, 9,
+        ];
+        assert_eq!(minimize_with_regret(&matrix, 3, 2), vec![Some((1, 1)), Some((0, 0)), None]);
     }
 }